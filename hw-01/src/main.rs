@@ -0,0 +1,4 @@
+fn main() {
+    let name = "Mark";
+    println!("Hello, world! My name is {name}.");
+}
@@ -0,0 +1,30 @@
+use std::env;
+use std::io::{self, Read};
+
+use slug::slugify;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 2 {
+        eprintln!("Usage: {} <lowercase|uppercase|no-spaces|slugify>", args[0]);
+        std::process::exit(1);
+    }
+
+    let mut input = String::new();
+    io::stdin()
+        .read_to_string(&mut input)
+        .expect("failed to read stdin");
+
+    let output = match args[1].as_str() {
+        "lowercase" => input.to_lowercase(),
+        "uppercase" => input.to_uppercase(),
+        "no-spaces" => input.replace(' ', ""),
+        "slugify" => slugify(&input),
+        other => {
+            eprintln!("Unknown transmutation: {other}");
+            std::process::exit(1);
+        }
+    };
+
+    println!("{output}");
+}
@@ -0,0 +1,99 @@
+use std::env;
+use std::error::Error;
+use std::fmt::Write as _;
+use std::io::{self, Read};
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 2 {
+        eprintln!("Usage: {} <lowercase|uppercase|no-spaces|slugify|csv>", args[0]);
+        std::process::exit(1);
+    }
+
+    let mut input = String::new();
+    if let Err(e) = io::stdin().read_to_string(&mut input) {
+        eprintln!("Failed to read stdin: {e}");
+        std::process::exit(1);
+    }
+
+    match transmute(&args[1], input) {
+        Ok(output) => println!("{output}"),
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn transmute(command: &str, input: String) -> Result<String, Box<dyn Error>> {
+    match command {
+        "lowercase" => Ok(input.to_lowercase()),
+        "uppercase" => Ok(input.to_uppercase()),
+        "no-spaces" => Ok(input.replace(' ', "")),
+        "slugify" => Ok(slug::slugify(&input)),
+        "csv" => parse_csv(&input),
+        other => Err(format!("Unknown command: {other}").into()),
+    }
+}
+
+/// Parses the input as CSV and renders it as a bordered table.
+fn parse_csv(input: &str) -> Result<String, Box<dyn Error>> {
+    let mut reader = csv::Reader::from_reader(input.as_bytes());
+
+    let headers: Vec<String> = reader.headers()?.iter().map(str::to_string).collect();
+    if headers.is_empty() {
+        return Err("CSV input has no header row".into());
+    }
+
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        rows.push(record.iter().map(str::to_string).collect());
+    }
+
+    Ok(format_table(&headers, &rows))
+}
+
+fn format_table(headers: &[String], rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in rows {
+        for (i, cell) in row.iter().enumerate() {
+            if i < widths.len() {
+                widths[i] = widths[i].max(cell.chars().count());
+            }
+        }
+    }
+
+    let mut out = String::new();
+    push_border(&mut out, &widths, '┌', '┬', '┐');
+    push_row(&mut out, headers, &widths);
+    push_border(&mut out, &widths, '├', '┼', '┤');
+    for row in rows {
+        push_row(&mut out, row, &widths);
+    }
+    push_border(&mut out, &widths, '└', '┴', '┘');
+    out
+}
+
+fn push_border(out: &mut String, widths: &[usize], left: char, mid: char, right: char) {
+    out.push(left);
+    for (i, w) in widths.iter().enumerate() {
+        if i > 0 {
+            out.push(mid);
+        }
+        for _ in 0..w + 2 {
+            out.push('─');
+        }
+    }
+    out.push(right);
+    out.push('\n');
+}
+
+fn push_row(out: &mut String, cells: &[String], widths: &[usize]) {
+    out.push('│');
+    for (i, w) in widths.iter().enumerate() {
+        let cell = cells.get(i).map(String::as_str).unwrap_or("");
+        let _ = write!(out, " {:<width$} │", cell, width = w);
+    }
+    out.push('\n');
+}
@@ -0,0 +1,80 @@
+use crate::text_utils::{SubCommand, TransformError};
+
+/// Renders whitespace-separated numbers as horizontal bars, one per
+/// value, scaled so the largest magnitude fills `width:<n>` columns
+/// (default 40). Labels are the values themselves, right-aligned.
+/// Negative values are scaled by absolute value, so `-4` draws as long
+/// a bar as `4`. Non-numeric tokens are skipped with a warning on
+/// stderr rather than aborting the chart.
+pub fn histogram(sub: &SubCommand, input: &str) -> Result<String, TransformError> {
+    let width: usize = sub.get_parsed("width")?.unwrap_or(40);
+    if width == 0 {
+        return Err(TransformError::InvalidArguments(
+            "width must be positive".to_string(),
+        ));
+    }
+
+    let mut values: Vec<f64> = Vec::new();
+    for token in input.split_whitespace() {
+        match token.parse::<f64>() {
+            Ok(value) => values.push(value),
+            Err(_) => eprintln!("histogram: skipping non-numeric token '{token}'"),
+        }
+    }
+    if values.is_empty() {
+        return Err(TransformError::InvalidArguments(
+            "no numeric input to chart".to_string(),
+        ));
+    }
+
+    let max_abs = values.iter().map(|v| v.abs()).fold(0.0, f64::max);
+    let label_width = values
+        .iter()
+        .map(|v| v.to_string().chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let lines: Vec<String> = values
+        .iter()
+        .map(|value| {
+            let bar_len = if max_abs == 0.0 {
+                0
+            } else {
+                (value.abs() / max_abs * width as f64).round() as usize
+            };
+            format!("{value:>label_width$} ┤ {}", "█".repeat(bar_len))
+        })
+        .collect();
+    Ok(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar_len(line: &str) -> usize {
+        line.chars().filter(|&c| c == '█').count()
+    }
+
+    #[test]
+    fn bars_scale_relative_to_the_maximum() {
+        let sub = SubCommand::parse(&["width:8".to_string()]).unwrap();
+        let out = histogram(&sub, "1 2 4").unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(bar_len(lines[0]), 2);
+        assert_eq!(bar_len(lines[1]), 4);
+        assert_eq!(bar_len(lines[2]), 8);
+    }
+
+    #[test]
+    fn negatives_chart_by_magnitude_and_junk_is_skipped() {
+        let sub = SubCommand::parse(&["width:4".to_string()]).unwrap();
+        let out = histogram(&sub, "-4 oops 2").unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 2, "got:\n{out}");
+        assert_eq!(bar_len(lines[0]), 4);
+        assert_eq!(bar_len(lines[1]), 2);
+
+        assert!(histogram(&SubCommand::default(), "no numbers here").is_err());
+    }
+}
@@ -0,0 +1,204 @@
+use crate::text_utils::{SubCommand, TransformError};
+
+/// Comment syntax for one language family: which characters open a
+/// string literal, which markers start a line comment, and the optional
+/// block comment pair.
+struct CommentStyle {
+    quotes: &'static [char],
+    line: &'static [&'static str],
+    block: Option<(&'static str, &'static str)>,
+}
+
+/// Removes comments from source code, `lang:c|rust|python|shell`
+/// (default `c`). A small lexer tracks string literals so a marker like
+/// `"// not a comment"` inside one is left alone.
+pub fn strip_comments(sub: &SubCommand, input: &str) -> Result<String, TransformError> {
+    let lang = sub.get("lang").unwrap_or("c");
+    let style = match lang {
+        "c" => CommentStyle {
+            quotes: &['"', '\''],
+            line: &["//"],
+            block: Some(("/*", "*/")),
+        },
+        // No '\'' here: in Rust it is far more often a lifetime than a
+        // char literal, and mistaking one swallows the rest of the file.
+        "rust" => CommentStyle {
+            quotes: &['"'],
+            line: &["//"],
+            block: Some(("/*", "*/")),
+        },
+        "python" | "shell" => CommentStyle {
+            quotes: &['"', '\''],
+            line: &["#"],
+            block: None,
+        },
+        other => {
+            return Err(TransformError::InvalidArguments(format!(
+                "unknown lang '{other}'; expected c, rust, python, or shell"
+            )))
+        }
+    };
+    Ok(strip(input, &style))
+}
+
+fn strip(input: &str, style: &CommentStyle) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut i = 0;
+    while i < input.len() {
+        let rest = &input[i..];
+        let c = rest.chars().next().expect("i is on a char boundary");
+
+        // String literal: copy verbatim through the closing quote,
+        // honouring backslash escapes.
+        if style.quotes.contains(&c) {
+            out.push(c);
+            i += c.len_utf8();
+            let mut escaped = false;
+            while i < input.len() {
+                let ch = input[i..].chars().next().expect("i is on a char boundary");
+                out.push(ch);
+                i += ch.len_utf8();
+                if escaped {
+                    escaped = false;
+                } else if ch == '\\' {
+                    escaped = true;
+                } else if ch == c {
+                    break;
+                }
+            }
+            continue;
+        }
+
+        if style.line.iter().any(|marker| rest.starts_with(marker)) {
+            i += rest.find('\n').unwrap_or(rest.len());
+            continue;
+        }
+
+        if let Some((open, close)) = style.block {
+            if let Some(after) = rest.strip_prefix(open) {
+                i += match after.find(close) {
+                    Some(n) => open.len() + n + close.len(),
+                    // Unterminated block comment runs to end of input.
+                    None => rest.len(),
+                };
+                continue;
+            }
+        }
+
+        out.push(c);
+        i += c.len_utf8();
+    }
+
+    // A removed end-of-line comment leaves the spaces that positioned it;
+    // trim them so the result has no trailing whitespace.
+    let mut trimmed = out
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n");
+    if out.ends_with('\n') {
+        trimmed.push('\n');
+    }
+    trimmed
+}
+
+/// Re-indents code to `to:<n>` spaces per nesting level. The source unit
+/// comes from `from:<n>`, or is detected: tabs count as one level each,
+/// and otherwise the smallest space indent in the input is taken as one
+/// level. Mixed or uneven indentation is normalized with a warning.
+pub fn reindent(sub: &SubCommand, input: &str) -> Result<String, TransformError> {
+    let to = sub.get_parsed::<usize>("to")?.ok_or_else(|| {
+        TransformError::InvalidArguments("reindent requires to:<n>".to_string())
+    })?;
+    let from = match sub.get_parsed::<usize>("from")? {
+        Some(0) => {
+            return Err(TransformError::InvalidArguments(
+                "from must be at least 1".to_string(),
+            ))
+        }
+        Some(n) => n,
+        None => detect_indent_unit(input),
+    };
+
+    let mut warned = false;
+    let mut lines = Vec::new();
+    for line in input.lines() {
+        let body = line.trim_start_matches([' ', '\t']);
+        if body.is_empty() {
+            lines.push(String::new());
+            continue;
+        }
+        let prefix = &line[..line.len() - body.len()];
+        let tabs = prefix.matches('\t').count();
+        let spaces = prefix.len() - tabs;
+        if !warned && (tabs > 0 && spaces > 0 || spaces % from != 0) {
+            eprintln!("reindent: mixed or uneven indentation, normalizing to the nearest level");
+            warned = true;
+        }
+        // Tabs are one level each; spaces round to the nearest level.
+        let depth = tabs + (spaces + from / 2) / from;
+        lines.push(format!("{}{body}", " ".repeat(depth * to)));
+    }
+
+    let mut out = lines.join("\n");
+    if input.ends_with('\n') {
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// The smallest space indent found, taken as one nesting level; 4 when
+/// the input gives no hint.
+fn detect_indent_unit(input: &str) -> usize {
+    input
+        .lines()
+        .filter(|line| line.starts_with(' '))
+        .map(|line| line.len() - line.trim_start_matches(' ').len())
+        .min()
+        .unwrap_or(4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn no_args() -> SubCommand {
+        SubCommand::parse(&[]).unwrap()
+    }
+
+    #[test]
+    fn line_and_block_comments_are_removed() {
+        let src = "int x = 1; // counter\n/* setup\n   code */int y = 2;\n";
+        let out = strip_comments(&no_args(), src).unwrap();
+        assert_eq!(out, "int x = 1;\nint y = 2;\n");
+    }
+
+    #[test]
+    fn comment_markers_inside_string_literals_survive() {
+        let src = "url = \"https://example.com\" // trailing\nc = '/' /* slash */\n";
+        let out = strip_comments(&no_args(), src).unwrap();
+        assert_eq!(out, "url = \"https://example.com\"\nc = '/'\n");
+    }
+
+    #[test]
+    fn reindent_widens_two_space_code_to_four() {
+        let src = "fn main() {\n  if x {\n    y();\n  }\n}\n";
+        let sub = SubCommand::parse(&["to:4".to_string()]).unwrap();
+        let out = reindent(&sub, src).unwrap();
+        assert_eq!(out, "fn main() {\n    if x {\n        y();\n    }\n}\n");
+    }
+
+    #[test]
+    fn reindent_treats_tabs_as_one_level_each() {
+        let sub = SubCommand::parse(&["to:2".to_string()]).unwrap();
+        let out = reindent(&sub, "a\n\tb\n\t\tc").unwrap();
+        assert_eq!(out, "a\n  b\n    c");
+    }
+
+    #[test]
+    fn python_hash_comments_respect_strings() {
+        let sub = SubCommand::parse(&["lang:python".to_string()]).unwrap();
+        let out = strip_comments(&sub, "tag = \"#hash\"  # real comment\n").unwrap();
+        assert_eq!(out, "tag = \"#hash\"\n");
+    }
+}
@@ -0,0 +1,1909 @@
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs;
+
+use is_terminal::IsTerminal;
+
+use crate::text_utils::{Registry, SubCommand, TransformError};
+
+/// Cells longer than this are truncated with an ellipsis in table output.
+pub const MAX_CELL_WIDTH: usize = 30;
+
+const ANSI_BOLD: &str = "\x1b[1m";
+const ANSI_RESET: &str = "\x1b[0m";
+/// Subtle dark-grey background for zebra striping.
+const ANSI_STRIPE: &str = "\x1b[48;5;236m";
+
+/// A parsed CSV document: header names plus data rows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Csv {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+/// Knobs for the plain-text table renderer.
+#[derive(Debug, Clone)]
+pub struct TableOptions {
+    /// Bold the header row (only sensible on a TTY).
+    pub color: bool,
+    /// Background-stripe alternate data rows (only sensible on a TTY).
+    pub zebra: bool,
+    /// Drawn between cells and at the junctions of border rows.
+    pub col_sep: char,
+    /// Centered title rendered above the top border.
+    pub caption: Option<String>,
+    /// Terminal width to fit within; trailing columns that would push the
+    /// table past it are dropped behind a `…` indicator column.
+    pub max_width: Option<usize>,
+    /// Prepend a 1-based `#` column for referencing rows.
+    pub rownum: bool,
+    /// Re-emit the header band after every this many data rows, for
+    /// tall tables read by scrolling; 0 disables it.
+    pub repeat_header: usize,
+    /// Render a footer band aggregating the numeric columns.
+    pub footer: Option<Agg>,
+}
+
+/// Aggregate computed per numeric column for the table footer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Agg {
+    Sum,
+    Avg,
+    Count,
+}
+
+impl Agg {
+    /// The label printed in the footer's first non-numeric cell.
+    fn label(self) -> &'static str {
+        match self {
+            Agg::Sum => "sum",
+            Agg::Avg => "avg",
+            Agg::Count => "count",
+        }
+    }
+}
+
+impl std::str::FromStr for Agg {
+    type Err = TransformError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sum" => Ok(Agg::Sum),
+            "avg" => Ok(Agg::Avg),
+            "count" => Ok(Agg::Count),
+            other => Err(TransformError::InvalidArguments(format!(
+                "unknown footer aggregate '{other}', expected sum, avg, or count"
+            ))),
+        }
+    }
+}
+
+impl Default for TableOptions {
+    fn default() -> Self {
+        TableOptions {
+            color: false,
+            zebra: false,
+            col_sep: '┆',
+            caption: None,
+            max_width: None,
+            rownum: false,
+            repeat_header: 0,
+            footer: None,
+        }
+    }
+}
+
+/// Summary statistics for one numeric column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stats {
+    pub count: usize,
+    /// Cells that did not parse as a number (including empty ones).
+    pub ignored: usize,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub median: f64,
+    pub sum: f64,
+}
+
+impl Stats {
+    pub fn render(&self, column: &str) -> String {
+        format!(
+            "column: {column}\ncount: {}\nignored: {}\nmin: {}\nmax: {}\nmean: {}\nmedian: {}\nsum: {}",
+            self.count, self.ignored, self.min, self.max, self.mean, self.median, self.sum
+        )
+    }
+}
+
+/// One recoverable problem found while parsing, reported by `errors:json`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseIssue {
+    /// 1-based line in the input, counting the header.
+    pub line: u64,
+    pub kind: String,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnType {
+    Integer,
+    Float,
+    Date,
+    Text,
+}
+
+/// Entry point for the `csv` command. Input comes from stdin unless a
+/// `p:<path>` argument points at a file.
+pub fn process_csv(sub: &SubCommand, input: String) -> Result<String, TransformError> {
+    let data = match sub.get("p") {
+        Some(path) => fs::read_to_string(path)?,
+        None => input,
+    };
+
+    let data = match sub.get("comment") {
+        Some(prefix) => strip_comment_lines(&data, prefix),
+        None => data,
+    };
+
+    let delimiter = match sub.get("d") {
+        Some(d) => parse_delimiter(d)?,
+        None => detect_delimiter(&data),
+    };
+
+    let collect_errors = match sub.get("errors") {
+        None => false,
+        Some("json") => true,
+        Some(other) => {
+            return Err(TransformError::InvalidArguments(format!(
+                "unknown errors mode '{other}', expected json"
+            )))
+        }
+    };
+
+    let (mut csv, issues) = if collect_errors {
+        parse_csv_data_lossy(&data, delimiter)?
+    } else {
+        (parse_csv_data(&data, delimiter)?, Vec::new())
+    };
+
+    if sub.get_bool("trim-cells") {
+        csv.trim_cells();
+    }
+
+    if sub.get_bool("sort-cols") {
+        csv.sort_columns();
+    }
+
+    if let Some(n) = sub.get_parsed::<usize>("sample")? {
+        csv = csv.sample(n, sub.get_parsed("seed")?);
+    }
+
+    if sub.get_bool("collapse") {
+        csv = csv.collapse_runs();
+    }
+
+    if let Some(spec) = sub.get("pivot") {
+        let (rows, cols, val, agg) = parse_pivot_spec(spec)?;
+        csv = csv.pivot(&rows, &cols, &val, agg)?;
+    }
+
+    if let Some(spec) = sub.get("apply") {
+        let (column, command) = spec.split_once(':').ok_or_else(|| {
+            TransformError::InvalidArguments(format!(
+                "apply expects <column>:<command>, got '{spec}'"
+            ))
+        })?;
+        csv.apply_column(column, command)?;
+    }
+
+    if let Some(spec) = sub.get("format-num") {
+        let (column, pattern) = spec.split_once(':').ok_or_else(|| {
+            TransformError::InvalidArguments(format!(
+                "format-num expects <column>:<pattern>, got '{spec}'"
+            ))
+        })?;
+        let (decimals, thousands) = parse_number_pattern(pattern)?;
+        csv.format_numeric_column(column, decimals, thousands)?;
+    }
+
+    // Last, so every earlier step still sees the plain column names.
+    if sub.get_bool("type-icons") {
+        csv.decorate_headers_with_types(std::io::stdout().is_terminal());
+    }
+
+    let rendered = if sub.get_bool("types") {
+        csv.type_report()
+    } else if let Some(column) = sub.get("stats") {
+        csv.column_stats(column)?.render(column)
+    } else if sub.get_bool("dupes") {
+        let dupes = csv.find_duplicates(sub.get("key"))?;
+        if dupes.is_empty() {
+            "no duplicates".to_string()
+        } else {
+            dupes
+                .iter()
+                .map(|(value, count)| format!("{value}: {count}"))
+                .collect::<Vec<String>>()
+                .join("\n")
+        }
+    } else if let Some(n) = sub.get_parsed::<usize>("row")? {
+        csv.row_record(n)?
+    } else if let Some(column) = sub.get("split-by") {
+        let outdir = sub.get("outdir").ok_or_else(|| {
+            TransformError::InvalidArguments("split-by requires outdir:<dir>".to_string())
+        })?;
+        csv.split_by(column, std::path::Path::new(outdir), delimiter as char)?
+    } else {
+        match sub.get("f").unwrap_or("table") {
+            "table" => {
+                let on_tty = std::io::stdout().is_terminal();
+                let max_width = match sub.get_parsed::<usize>("term-width")? {
+                    Some(limit) => Some(limit),
+                    None => terminal_size::terminal_size().map(|(w, _)| w.0 as usize),
+                };
+                let too_wide = max_width.is_some_and(|limit| csv.table_width() > limit);
+                match sub.get("overflow").unwrap_or("truncate") {
+                    "records" if too_wide => csv.to_records(),
+                    "truncate" | "records" => {
+                        let options = TableOptions {
+                            color: sub.get_bool("color") && on_tty,
+                            zebra: sub.get_bool("zebra") && on_tty,
+                            col_sep: match sub.get("col-sep") {
+                                Some(sep) => parse_col_sep(sep)?,
+                                None => TableOptions::default().col_sep,
+                            },
+                            caption: sub.get("caption").map(str::to_string),
+                            max_width,
+                            rownum: sub.get_bool("rownum"),
+                            repeat_header: sub.get_parsed("repeat-header")?.unwrap_or(0),
+                            footer: sub.get_parsed("footer")?,
+                        };
+                        csv.format_as_table(&options)
+                    }
+                    other => {
+                        return Err(TransformError::InvalidArguments(format!(
+                            "unknown overflow policy '{other}', expected truncate or records"
+                        )))
+                    }
+                }
+            }
+            "records" => csv.to_records(),
+            "json" => csv.to_json()?,
+            "yaml" => csv.to_yaml(sub.get_bool("raw-nums"))?,
+            "markdown" => csv.to_markdown(),
+            // Markdown table folded into a `<details>` block, for long
+            // tables in issues. `summary:<label>` sets the visible text.
+            "details" => {
+                let label = match sub.get("summary") {
+                    Some(label) => label.to_string(),
+                    None => format!("{} rows", csv.rows.len()),
+                };
+                // The blank lines are required for Markdown to render
+                // inside the HTML block.
+                format!(
+                    "<details><summary>{label}</summary>\n\n{}\n\n</details>",
+                    csv.to_markdown()
+                )
+            }
+            "csv" => csv.to_delimited(delimiter as char),
+            "sql" => {
+                let table = sub.get("table").ok_or_else(|| {
+                    TransformError::InvalidArguments(
+                        "f:sql requires table:<name>".to_string(),
+                    )
+                })?;
+                csv.to_sql(table, sub.get_bool("raw-nums"))
+            }
+            other => {
+                return Err(TransformError::InvalidArguments(format!(
+                    "unknown csv output format: {other}"
+                )))
+            }
+        }
+    };
+
+    if collect_errors {
+        return Ok(format!("{rendered}\n{}", issues_to_json(&issues)));
+    }
+    Ok(rendered)
+}
+
+/// Entry point for the `csv-check` command: validates without rendering.
+/// The first problem becomes an error naming its line and kind (so the
+/// process exits non-zero); clean input gets a one-line summary.
+pub fn check_csv(sub: &SubCommand, input: String) -> Result<String, TransformError> {
+    let data = match sub.get("p") {
+        Some(path) => fs::read_to_string(path)?,
+        None => input,
+    };
+    let data = match sub.get("comment") {
+        Some(prefix) => strip_comment_lines(&data, prefix),
+        None => data,
+    };
+    let delimiter = match sub.get("d") {
+        Some(d) => parse_delimiter(d)?,
+        None => detect_delimiter(&data),
+    };
+
+    let (csv, issues) = parse_csv_data_lossy(&data, delimiter)?;
+    if let Some(first) = issues.first() {
+        return Err(TransformError::Csv(format!(
+            "line {}: {}: {}",
+            first.line, first.kind, first.detail
+        )));
+    }
+    Ok(format!(
+        "ok: {} rows, {} columns",
+        csv.rows.len(),
+        csv.columns.len()
+    ))
+}
+
+fn issues_to_json(issues: &[ParseIssue]) -> String {
+    let values: Vec<serde_json::Value> = issues
+        .iter()
+        .map(|issue| {
+            serde_json::json!({
+                "line": issue.line,
+                "kind": issue.kind,
+                "detail": issue.detail,
+            })
+        })
+        .collect();
+    serde_json::Value::Array(values).to_string()
+}
+
+/// Drops lines whose first non-whitespace characters are `prefix`, so
+/// `#`-commented CSV-ish files parse cleanly. The header becomes the
+/// first non-comment line.
+fn strip_comment_lines(data: &str, prefix: &str) -> String {
+    data.lines()
+        .filter(|line| !line.trim_start().starts_with(prefix))
+        .collect::<Vec<&str>>()
+        .join("\n")
+}
+
+fn parse_col_sep(s: &str) -> Result<char, TransformError> {
+    match s {
+        "│" => Ok('│'),
+        "┆" => Ok('┆'),
+        "|" => Ok('|'),
+        "space" | " " => Ok(' '),
+        other => Err(TransformError::InvalidArguments(format!(
+            "col-sep must be │, ┆, |, or space, got '{other}'"
+        ))),
+    }
+}
+
+/// Entry point for the `csv-dialect` command: reports the detected
+/// delimiter, quoting, header presence, and line-ending style without
+/// rendering the data. The header heuristic — first row all
+/// non-numeric — misfires on tables of text, so the report is a
+/// starting point for configuring the other commands, not gospel.
+pub fn dialect(sub: &SubCommand, input: String) -> Result<String, TransformError> {
+    let data = match sub.get("p") {
+        Some(path) => fs::read_to_string(path)?,
+        None => input,
+    };
+
+    let delimiter = detect_delimiter(&data);
+    let delimiter_name = match delimiter {
+        b'\t' => "tab".to_string(),
+        d => char::from(d).to_string(),
+    };
+    let quote = if data.contains('"') { "\"" } else { "none" };
+    let first_row_non_numeric = data
+        .lines()
+        .next()
+        .unwrap_or("")
+        .split(char::from(delimiter))
+        .all(|cell| cell.trim_matches('"').parse::<f64>().is_err());
+    let header = if first_row_non_numeric { "yes" } else { "no" };
+    let line_endings = match (data.contains("\r\n"), data.contains('\n')) {
+        (true, _) => "crlf",
+        (false, true) => "lf",
+        (false, false) => "none",
+    };
+
+    Ok(format!(
+        "delimiter: {delimiter_name}\nquote: {quote}\nheader: {header}\nline-endings: {line_endings}"
+    ))
+}
+
+fn parse_delimiter(d: &str) -> Result<u8, TransformError> {
+    match d {
+        "tab" | "\\t" => Ok(b'\t'),
+        s if s.len() == 1 && s.is_ascii() => Ok(s.as_bytes()[0]),
+        other => Err(TransformError::InvalidArguments(format!(
+            "delimiter must be a single ASCII character, got '{other}'"
+        ))),
+    }
+}
+
+/// Guesses the delimiter by counting candidate characters in the first line.
+pub fn detect_delimiter(data: &str) -> u8 {
+    let first_line = data.lines().next().unwrap_or("");
+    let candidates = [b',', b';', b'\t', b'|'];
+    candidates
+        .into_iter()
+        .max_by_key(|&c| first_line.bytes().filter(|&b| b == c).count())
+        .unwrap_or(b',')
+}
+
+pub fn parse_csv_data(data: &str, delimiter: u8) -> Result<Csv, TransformError> {
+    let (csv, issues) = parse_csv_data_lossy(data, delimiter)?;
+    if let Some(first) = issues.first() {
+        return Err(TransformError::Csv(first.detail.clone()));
+    }
+    Ok(csv)
+}
+
+/// Like [`parse_csv_data`] but keeps going after recoverable record
+/// errors (ragged rows, bad quoting), accumulating them as
+/// [`ParseIssue`]s. A missing header is still fatal.
+pub fn parse_csv_data_lossy(
+    data: &str,
+    delimiter: u8,
+) -> Result<(Csv, Vec<ParseIssue>), TransformError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .from_reader(data.as_bytes());
+
+    let columns: Vec<String> = reader
+        .headers()
+        .map_err(|e| TransformError::Csv(e.to_string()))?
+        .iter()
+        .map(str::to_string)
+        .collect();
+    if columns.is_empty() || (columns.len() == 1 && columns[0].is_empty()) {
+        return Err(TransformError::Csv("input has no header row".to_string()));
+    }
+
+    let mut rows = Vec::new();
+    let mut issues = Vec::new();
+    for record in reader.records() {
+        match record {
+            Ok(record) => rows.push(record.iter().map(str::to_string).collect()),
+            Err(e) => issues.push(issue_from(&e)),
+        }
+    }
+
+    Ok((Csv { columns, rows }, issues))
+}
+
+fn issue_from(e: &csv::Error) -> ParseIssue {
+    let (line, kind) = match e.kind() {
+        csv::ErrorKind::UnequalLengths { pos, .. } => (
+            pos.as_ref().map(|p| p.line()).unwrap_or(0),
+            "unequal-lengths",
+        ),
+        csv::ErrorKind::Utf8 { pos, .. } => (pos.as_ref().map(|p| p.line()).unwrap_or(0), "utf8"),
+        _ => (e.position().map(|p| p.line()).unwrap_or(0), "parse"),
+    };
+    ParseIssue {
+        line,
+        kind: kind.to_string(),
+        detail: e.to_string(),
+    }
+}
+
+fn display_width(s: &str) -> usize {
+    s.chars().count()
+}
+
+fn truncate_cell(s: &str, width: usize) -> String {
+    if display_width(s) <= width {
+        s.to_string()
+    } else {
+        let mut out: String = s.chars().take(width.saturating_sub(1)).collect();
+        out.push('…');
+        out
+    }
+}
+
+impl Csv {
+    /// Reorders columns alphabetically by header name, permuting every
+    /// row's cells to match. Duplicate header names keep their relative
+    /// order (the sort is stable).
+    pub fn sort_columns(&mut self) {
+        let mut order: Vec<usize> = (0..self.columns.len()).collect();
+        order.sort_by(|&a, &b| self.columns[a].cmp(&self.columns[b]));
+
+        self.columns = order.iter().map(|&i| self.columns[i].clone()).collect();
+        for row in &mut self.rows {
+            *row = order
+                .iter()
+                .map(|&i| row.get(i).cloned().unwrap_or_default())
+                .collect();
+        }
+    }
+
+    /// Trims each header and cell and collapses internal whitespace runs
+    /// to single spaces. Spreadsheet copy-paste leaves padding and tabs
+    /// that would otherwise skew the computed column widths.
+    pub fn trim_cells(&mut self) {
+        fn clean(s: &str) -> String {
+            s.split_whitespace().collect::<Vec<&str>>().join(" ")
+        }
+        self.columns = self.columns.iter().map(|c| clean(c)).collect();
+        for row in &mut self.rows {
+            *row = row.iter().map(|c| clean(c)).collect();
+        }
+    }
+
+    /// Position of a column by header name.
+    pub fn column_index(&self, column: &str) -> Result<usize, TransformError> {
+        self.columns
+            .iter()
+            .position(|c| c == column)
+            .ok_or_else(|| TransformError::Csv(format!("unknown column: {column}")))
+    }
+
+    /// Computes min/max/mean/median/sum over the cells of the named
+    /// column that parse as `f64`. Cells that don't parse are counted in
+    /// `ignored`; an all-non-numeric column reports NaN, not a panic.
+    pub fn column_stats(&self, column: &str) -> Result<Stats, TransformError> {
+        let index = self.column_index(column)?;
+
+        let mut values: Vec<f64> = Vec::new();
+        let mut ignored = 0usize;
+        for row in &self.rows {
+            let cell = row.get(index).map(String::as_str).unwrap_or("").trim();
+            match cell.parse::<f64>() {
+                Ok(value) => values.push(value),
+                Err(_) => ignored += 1,
+            }
+        }
+
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        let count = values.len();
+        let sum: f64 = values.iter().sum();
+        let (min, max, mean, median) = if count == 0 {
+            (f64::NAN, f64::NAN, f64::NAN, f64::NAN)
+        } else {
+            let median = if count % 2 == 1 {
+                values[count / 2]
+            } else {
+                (values[count / 2 - 1] + values[count / 2]) / 2.0
+            };
+            (values[0], values[count - 1], sum / count as f64, median)
+        };
+
+        Ok(Stats {
+            count,
+            ignored,
+            min,
+            max,
+            mean,
+            median,
+            sum,
+        })
+    }
+
+    /// One footer cell per column: the aggregate where every non-empty
+    /// cell is numeric, an empty cell elsewhere. The first non-numeric
+    /// column carries the aggregate's label instead, so the band is
+    /// self-describing.
+    pub fn footer_row(&self, agg: Agg) -> Vec<String> {
+        let mut cells: Vec<String> = (0..self.columns.len())
+            .map(|i| {
+                let values: Vec<f64> = self
+                    .rows
+                    .iter()
+                    .filter_map(|row| row.get(i))
+                    .filter(|cell| !cell.is_empty())
+                    .map(|cell| cell.parse::<f64>())
+                    .collect::<Result<_, _>>()
+                    .unwrap_or_default();
+                if values.is_empty() {
+                    return String::new();
+                }
+                match agg {
+                    Agg::Sum => format_agg(values.iter().sum()),
+                    Agg::Avg => format_agg(values.iter().sum::<f64>() / values.len() as f64),
+                    Agg::Count => values.len().to_string(),
+                }
+            })
+            .collect();
+        if let Some(first) = cells.first_mut() {
+            if first.is_empty() {
+                *first = agg.label().to_string();
+            }
+        }
+        cells
+    }
+
+    /// Copy with a leading 1-based `#` column; the numbers are render
+    /// furniture, not data.
+    fn with_row_numbers(&self) -> Csv {
+        let mut columns = vec!["#".to_string()];
+        columns.extend(self.columns.iter().cloned());
+        let rows = self
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                let mut cells = vec![(i + 1).to_string()];
+                cells.extend(row.iter().cloned());
+                cells
+            })
+            .collect();
+        Csv { columns, rows }
+    }
+
+    fn column_widths(&self) -> Vec<usize> {
+        let mut widths: Vec<usize> = self.columns.iter().map(|h| display_width(h)).collect();
+        for row in &self.rows {
+            for (i, cell) in row.iter().enumerate() {
+                if i < widths.len() {
+                    widths[i] = widths[i].max(display_width(cell));
+                }
+            }
+        }
+        widths.iter().map(|w| (*w).min(MAX_CELL_WIDTH)).collect()
+    }
+
+    /// Full rendered width of the table: each column costs its width plus
+    /// padding and a separator, plus the leading separator.
+    pub fn table_width(&self) -> usize {
+        total_width(&self.column_widths())
+    }
+
+    /// Renders the table with light `┄` borders and `col_sep` between
+    /// cells; the separator also marks the junctions of border rows.
+    /// With `max_width` set, trailing columns that do not fit collapse
+    /// into a one-wide `…` indicator column.
+    pub fn format_as_table(&self, options: &TableOptions) -> String {
+        if options.rownum {
+            let numbered = self.with_row_numbers();
+            let options = TableOptions {
+                rownum: false,
+                ..options.clone()
+            };
+            return numbered.format_as_table(&options);
+        }
+        let footer = options.footer.map(|agg| self.footer_row(agg));
+        let mut widths = self.column_widths();
+        if let Some(cells) = &footer {
+            for (i, cell) in cells.iter().enumerate() {
+                if i < widths.len() {
+                    widths[i] = widths[i].max(display_width(cell).min(MAX_CELL_WIDTH));
+                }
+            }
+        }
+        let mut clipped = false;
+        if let Some(limit) = options.max_width {
+            if total_width(&widths) > limit {
+                widths.truncate(fitting_columns(&widths, limit));
+                widths.push(1); // the `…` indicator column
+                clipped = true;
+            }
+        }
+        let total = total_width(&widths);
+        let clip = |cells: &[String]| -> Vec<String> {
+            let mut kept: Vec<String> =
+                cells.iter().take(widths.len() - 1).cloned().collect();
+            kept.resize(widths.len() - 1, String::new());
+            kept.push("…".to_string());
+            kept
+        };
+        let make_border = |dash: &str| -> String {
+            let mut b = String::new();
+            b.push(options.col_sep);
+            for width in &widths {
+                b.push_str(&dash.repeat(width + 2));
+                b.push(options.col_sep);
+            }
+            b
+        };
+        let border = make_border("┄");
+
+        let mut out = String::new();
+        if let Some(caption) = &options.caption {
+            for line in wrap_words(caption, total) {
+                let pad = (total.saturating_sub(display_width(&line))) / 2;
+                let _ = writeln!(out, "{}{line}", " ".repeat(pad));
+            }
+        }
+        out.push_str(&border);
+        out.push('\n');
+        let header_style = options.color.then_some(ANSI_BOLD);
+        let clipped_header;
+        let header: &[String] = if clipped {
+            clipped_header = clip(&self.columns);
+            &clipped_header
+        } else {
+            &self.columns
+        };
+        self.push_table_row(&mut out, header, &widths, header_style, options.col_sep);
+        out.push_str(&border);
+        out.push('\n');
+        for (i, row) in self.rows.iter().enumerate() {
+            if options.repeat_header > 0 && i > 0 && i % options.repeat_header == 0 {
+                out.push_str(&border);
+                out.push('\n');
+                self.push_table_row(&mut out, header, &widths, header_style, options.col_sep);
+                out.push_str(&border);
+                out.push('\n');
+            }
+            let style = (options.zebra && i % 2 == 1).then_some(ANSI_STRIPE);
+            let clipped_row;
+            let cells: &[String] = if clipped {
+                clipped_row = clip(row);
+                &clipped_row
+            } else {
+                row
+            };
+            self.push_table_row(&mut out, cells, &widths, style, options.col_sep);
+        }
+        if let Some(cells) = &footer {
+            // A heavier dash sets the footer band off from the data.
+            out.push_str(&make_border("┅"));
+            out.push('\n');
+            let clipped_footer;
+            let cells: &[String] = if clipped {
+                clipped_footer = clip(cells);
+                &clipped_footer
+            } else {
+                cells
+            };
+            self.push_table_row(&mut out, cells, &widths, header_style, options.col_sep);
+        }
+        out.push_str(&border);
+        out
+    }
+
+    fn push_table_row(
+        &self,
+        out: &mut String,
+        cells: &[String],
+        widths: &[usize],
+        style: Option<&str>,
+        sep: char,
+    ) {
+        out.push(sep);
+        for (i, width) in widths.iter().enumerate() {
+            let cell = cells.get(i).map(String::as_str).unwrap_or("");
+            let cell = truncate_cell(cell, *width);
+            let pad = " ".repeat(width - display_width(&cell));
+            match style {
+                Some(code) => {
+                    let _ = write!(out, " {code}{cell}{pad}{ANSI_RESET} {sep}");
+                }
+                None => {
+                    let _ = write!(out, " {cell}{pad} {sep}");
+                }
+            }
+        }
+        out.push('\n');
+    }
+
+    /// Renders each row as a `header: value` block, blocks separated by
+    /// blank lines. Far more readable than a table for wide data with
+    /// few rows. Missing cells print an empty value.
+    pub fn to_records(&self) -> String {
+        let blocks: Vec<String> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let lines: Vec<String> = self
+                    .columns
+                    .iter()
+                    .enumerate()
+                    .map(|(i, col)| {
+                        format!("{col}: {}", row.get(i).map(String::as_str).unwrap_or(""))
+                    })
+                    .collect();
+                lines.join("\n")
+            })
+            .collect();
+        blocks.join("\n\n")
+    }
+
+    /// Reformats every numeric cell of `column` with a fixed number of
+    /// decimals and, optionally, comma thousands separators. Cells that
+    /// do not parse as numbers are left as-is, so a stray `n/a` never
+    /// breaks the column.
+    pub fn format_numeric_column(
+        &mut self,
+        column: &str,
+        decimals: usize,
+        thousands: bool,
+    ) -> Result<(), TransformError> {
+        let index = self.column_index(column)?;
+        for row in &mut self.rows {
+            if let Some(cell) = row.get_mut(index) {
+                if let Ok(value) = cell.parse::<f64>() {
+                    *cell = format_number(value, decimals, thousands);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Builds a pivot table: unique values of `rows` become row
+    /// headers, unique values of `cols` become column headers (both in
+    /// first-seen order), and each cell aggregates the `val` column
+    /// over the matching records. Combinations that never occur render
+    /// empty. Sum and avg require numeric values; count does not.
+    pub fn pivot(
+        &self,
+        rows: &str,
+        cols: &str,
+        val: &str,
+        agg: Agg,
+    ) -> Result<Csv, TransformError> {
+        let row_idx = self.column_index(rows)?;
+        let col_idx = self.column_index(cols)?;
+        let val_idx = self.column_index(val)?;
+
+        let mut row_keys: Vec<String> = Vec::new();
+        let mut col_keys: Vec<String> = Vec::new();
+        let mut cells: HashMap<(String, String), Vec<String>> = HashMap::new();
+        for record in &self.rows {
+            let empty = String::new();
+            let row_key = record.get(row_idx).unwrap_or(&empty).clone();
+            let col_key = record.get(col_idx).unwrap_or(&empty).clone();
+            if !row_keys.contains(&row_key) {
+                row_keys.push(row_key.clone());
+            }
+            if !col_keys.contains(&col_key) {
+                col_keys.push(col_key.clone());
+            }
+            cells
+                .entry((row_key, col_key))
+                .or_default()
+                .push(record.get(val_idx).unwrap_or(&empty).clone());
+        }
+
+        let aggregate = |values: &[String]| -> Result<String, TransformError> {
+            if agg == Agg::Count {
+                return Ok(values.len().to_string());
+            }
+            let numbers: Vec<f64> = values
+                .iter()
+                .map(|v| {
+                    v.parse::<f64>().map_err(|_| {
+                        TransformError::Csv(format!("non-numeric value '{v}' in column {val}"))
+                    })
+                })
+                .collect::<Result<_, _>>()?;
+            let sum: f64 = numbers.iter().sum();
+            Ok(match agg {
+                Agg::Sum => format_agg(sum),
+                Agg::Avg => format_agg(sum / numbers.len() as f64),
+                Agg::Count => unreachable!("handled above"),
+            })
+        };
+
+        let mut columns = vec![rows.to_string()];
+        columns.extend(col_keys.iter().cloned());
+        let mut out_rows = Vec::new();
+        for row_key in &row_keys {
+            let mut out_row = vec![row_key.clone()];
+            for col_key in &col_keys {
+                match cells.get(&(row_key.clone(), col_key.clone())) {
+                    Some(values) => out_row.push(aggregate(values)?),
+                    None => out_row.push(String::new()),
+                }
+            }
+            out_rows.push(out_row);
+        }
+        Ok(Csv {
+            columns,
+            rows: out_rows,
+        })
+    }
+
+    /// Merges runs of consecutive identical rows into one, run-length
+    /// style, with an appended `count` column holding the run length.
+    /// Unlike deduplication, identical rows separated by anything else
+    /// stay separate.
+    pub fn collapse_runs(&self) -> Csv {
+        let mut columns = self.columns.clone();
+        columns.push("count".to_string());
+
+        let mut rows: Vec<Vec<String>> = Vec::new();
+        let mut runs: Vec<(usize, usize)> = Vec::new(); // (row index, length)
+        for (i, row) in self.rows.iter().enumerate() {
+            match runs.last_mut() {
+                Some((start, len)) if &self.rows[*start] == row => *len += 1,
+                _ => runs.push((i, 1)),
+            }
+        }
+        for (start, len) in runs {
+            let mut row = self.rows[start].clone();
+            row.push(len.to_string());
+            rows.push(row);
+        }
+        Csv { columns, rows }
+    }
+
+    /// Runs one of the text transforms over every cell of `column`,
+    /// looked up through the transform registry so anything `pipe` or
+    /// the CLI accepts works here too. Unknown columns and commands
+    /// error; so does a transform failing on any cell.
+    pub fn apply_column(&mut self, column: &str, command: &str) -> Result<(), TransformError> {
+        let index = self.column_index(column)?;
+        let registry = Registry::new();
+        let sub = SubCommand::default();
+        for row in &mut self.rows {
+            if let Some(cell) = row.get_mut(index) {
+                *cell = registry.transmute(command, &sub, std::mem::take(cell))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// A random sample of `n` data rows via reservoir sampling, so the
+    /// rows are visited once in order and never all held twice. A seed
+    /// makes the selection reproducible; `n` past the row count keeps
+    /// every row. Sampled rows stay in their original order of arrival
+    /// through the reservoir, not sorted.
+    pub fn sample(&self, n: usize, seed: Option<u64>) -> Csv {
+        use rand::{Rng, SeedableRng};
+        let mut rng: rand::rngs::StdRng = match seed {
+            Some(seed) => SeedableRng::seed_from_u64(seed),
+            None => SeedableRng::from_entropy(),
+        };
+
+        let mut reservoir: Vec<Vec<String>> = Vec::with_capacity(n.min(self.rows.len()));
+        for (i, row) in self.rows.iter().enumerate() {
+            if reservoir.len() < n {
+                reservoir.push(row.clone());
+            } else {
+                let j = rng.gen_range(0..=i);
+                if j < n {
+                    reservoir[j] = row.clone();
+                }
+            }
+        }
+        Csv {
+            columns: self.columns.clone(),
+            rows: reservoir,
+        }
+    }
+
+    /// The record view of a single data row, counted from 1. An index
+    /// outside the data is an error naming the valid range.
+    pub fn row_record(&self, n: usize) -> Result<String, TransformError> {
+        if n == 0 || n > self.rows.len() {
+            return Err(TransformError::InvalidArguments(format!(
+                "row {n} is out of range, table has {} data rows",
+                self.rows.len()
+            )));
+        }
+        let single = Csv {
+            columns: self.columns.clone(),
+            rows: vec![self.rows[n - 1].clone()],
+        };
+        Ok(single.to_records())
+    }
+
+    /// Serializes back to delimiter-separated values, quoting when needed.
+    pub fn to_delimited(&self, delimiter: char) -> String {
+        let quote = |cell: &str| -> String {
+            if cell.contains(delimiter) || cell.contains('"') || cell.contains('\n') {
+                format!("\"{}\"", cell.replace('"', "\"\""))
+            } else {
+                cell.to_string()
+            }
+        };
+
+        let mut out = String::new();
+        let header: Vec<String> = self.columns.iter().map(|c| quote(c)).collect();
+        out.push_str(&header.join(&delimiter.to_string()));
+        out.push('\n');
+        for row in &self.rows {
+            let cells: Vec<String> = row.iter().map(|c| quote(c)).collect();
+            out.push_str(&cells.join(&delimiter.to_string()));
+            out.push('\n');
+        }
+        out.pop();
+        out
+    }
+
+    /// Emits the rows as a JSON array of objects keyed by header.
+    pub fn to_json(&self) -> Result<String, TransformError> {
+        let objects: Vec<serde_json::Value> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let map: serde_json::Map<String, serde_json::Value> = self
+                    .columns
+                    .iter()
+                    .enumerate()
+                    .map(|(i, col)| {
+                        let cell = row.get(i).map(String::as_str).unwrap_or("");
+                        (col.clone(), serde_json::Value::String(cell.to_string()))
+                    })
+                    .collect();
+                serde_json::Value::Object(map)
+            })
+            .collect();
+        serde_json::to_string_pretty(&objects)
+            .map_err(|e| TransformError::Other(format!("JSON serialization failed: {e}")))
+    }
+
+    /// Emits the rows as a YAML sequence of mappings keyed by header,
+    /// mirroring [`Csv::to_json`]. With `raw_numbers`, numeric-looking
+    /// cells become YAML numbers instead of strings.
+    pub fn to_yaml(&self, raw_numbers: bool) -> Result<String, TransformError> {
+        let cell_value = |cell: &str| -> serde_yaml::Value {
+            if raw_numbers {
+                if let Ok(n) = cell.parse::<i64>() {
+                    return serde_yaml::Value::Number(n.into());
+                }
+                if let Ok(n) = cell.parse::<f64>() {
+                    return serde_yaml::Value::Number(n.into());
+                }
+            }
+            serde_yaml::Value::String(cell.to_string())
+        };
+
+        let mappings: Vec<serde_yaml::Value> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let map: serde_yaml::Mapping = self
+                    .columns
+                    .iter()
+                    .enumerate()
+                    .map(|(i, col)| {
+                        let cell = row.get(i).map(String::as_str).unwrap_or("");
+                        (
+                            serde_yaml::Value::String(col.clone()),
+                            cell_value(cell),
+                        )
+                    })
+                    .collect();
+                serde_yaml::Value::Mapping(map)
+            })
+            .collect();
+        serde_yaml::to_string(&mappings)
+            .map_err(|e| TransformError::Other(format!("YAML serialization failed: {e}")))
+    }
+
+    /// Emits a GitHub-flavored Markdown table.
+    pub fn to_markdown(&self) -> String {
+        let escape = |cell: &str| cell.replace('|', "\\|");
+
+        let mut out = String::new();
+        let header: Vec<String> = self.columns.iter().map(|c| escape(c)).collect();
+        let _ = writeln!(out, "| {} |", header.join(" | "));
+        let sep: Vec<&str> = self.columns.iter().map(|_| "---").collect();
+        let _ = writeln!(out, "| {} |", sep.join(" | "));
+        for row in &self.rows {
+            let cells: Vec<String> = self
+                .columns
+                .iter()
+                .enumerate()
+                .map(|(i, _)| escape(row.get(i).map(String::as_str).unwrap_or("")))
+                .collect();
+            let _ = writeln!(out, "| {} |", cells.join(" | "));
+        }
+        out.pop();
+        out
+    }
+
+    /// Emits one `INSERT INTO <table>` statement per row. Values are
+    /// single-quoted with embedded quotes doubled; with `raw_numbers`,
+    /// numeric-looking cells go in unquoted.
+    pub fn to_sql(&self, table: &str, raw_numbers: bool) -> String {
+        let columns = self.columns.join(", ");
+        let statements: Vec<String> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let values: Vec<String> = (0..self.columns.len())
+                    .map(|i| {
+                        let cell = row.get(i).map(String::as_str).unwrap_or("");
+                        if raw_numbers && !cell.is_empty() && cell.parse::<f64>().is_ok() {
+                            cell.to_string()
+                        } else {
+                            format!("'{}'", cell.replace('\'', "''"))
+                        }
+                    })
+                    .collect();
+                format!(
+                    "INSERT INTO {table} ({columns}) VALUES ({});",
+                    values.join(", ")
+                )
+            })
+            .collect();
+        statements.join("\n")
+    }
+
+    /// Values that occur more than once, with their counts, in
+    /// first-seen order. With `key` only that column is compared;
+    /// otherwise whole rows are, reported comma-joined.
+    pub fn find_duplicates(
+        &self,
+        key: Option<&str>,
+    ) -> Result<Vec<(String, usize)>, TransformError> {
+        let index = match key {
+            Some(column) => Some(self.column_index(column)?),
+            None => None,
+        };
+
+        let mut counts: Vec<(String, usize)> = Vec::new();
+        for row in &self.rows {
+            let value = match index {
+                Some(i) => row.get(i).cloned().unwrap_or_default(),
+                None => row.join(","),
+            };
+            match counts.iter_mut().find(|(v, _)| *v == value) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((value, 1)),
+            }
+        }
+        counts.retain(|(_, count)| *count > 1);
+        Ok(counts)
+    }
+
+    /// Writes one file per distinct value of `column` into `outdir`,
+    /// each containing the header plus the matching rows. Filenames are
+    /// the slugified value; groups keep first-seen order. Returns a
+    /// summary line per file written.
+    pub fn split_by(
+        &self,
+        column: &str,
+        outdir: &std::path::Path,
+        delimiter: char,
+    ) -> Result<String, TransformError> {
+        let index = self.column_index(column)?;
+        fs::create_dir_all(outdir)?;
+
+        let mut groups: Vec<(String, Csv)> = Vec::new();
+        for row in &self.rows {
+            let value = row.get(index).map(String::as_str).unwrap_or("");
+            let mut key = slug::slugify(value);
+            if key.is_empty() {
+                key = "empty".to_string();
+            }
+            let group = match groups.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, group)) => group,
+                None => {
+                    groups.push((
+                        key,
+                        Csv {
+                            columns: self.columns.clone(),
+                            rows: Vec::new(),
+                        },
+                    ));
+                    &mut groups.last_mut().expect("group just pushed").1
+                }
+            };
+            group.rows.push(row.clone());
+        }
+
+        let mut summary = Vec::new();
+        for (key, group) in &groups {
+            let path = outdir.join(format!("{key}.csv"));
+            crate::fs_util::write_atomic(&path, group.to_delimited(delimiter).as_bytes())?;
+            summary.push(format!("{}: {} rows", path.display(), group.rows.len()));
+        }
+        Ok(summary.join("\n"))
+    }
+
+    /// Infers a type for each column from its cells.
+    pub fn infer_column_type(&self, index: usize) -> ColumnType {
+        let mut seen_any = false;
+        let mut all_int = true;
+        let mut all_float = true;
+        let mut all_date = true;
+
+        for row in &self.rows {
+            let cell = row.get(index).map(String::as_str).unwrap_or("").trim();
+            if cell.is_empty() {
+                continue;
+            }
+            seen_any = true;
+            if cell.parse::<i64>().is_err() {
+                all_int = false;
+            }
+            if cell.parse::<f64>().is_err() {
+                all_float = false;
+            }
+            if !looks_like_date(cell) {
+                all_date = false;
+            }
+        }
+
+        if !seen_any {
+            ColumnType::Text
+        } else if all_int {
+            ColumnType::Integer
+        } else if all_float {
+            ColumnType::Float
+        } else if all_date {
+            ColumnType::Date
+        } else {
+            ColumnType::Text
+        }
+    }
+
+    /// Lists each column with its inferred type.
+    /// Prefixes each header with a glyph for its inferred type (`#`
+    /// numeric, `A` text, calendar emoji for dates), for `type-icons:true`.
+    /// The emoji only appears on a terminal; plain `D` goes to pipes and
+    /// files where wide glyphs tend to break alignment.
+    pub fn decorate_headers_with_types(&mut self, on_tty: bool) {
+        let date_icon = if on_tty { "📅" } else { "D" };
+        for i in 0..self.columns.len() {
+            let icon = match self.infer_column_type(i) {
+                ColumnType::Integer | ColumnType::Float => "#",
+                ColumnType::Date => date_icon,
+                ColumnType::Text => "A",
+            };
+            self.columns[i] = format!("{icon} {}", self.columns[i]);
+        }
+    }
+
+    pub fn type_report(&self) -> String {
+        let mut out = String::new();
+        for (i, col) in self.columns.iter().enumerate() {
+            let kind = match self.infer_column_type(i) {
+                ColumnType::Integer => "integer",
+                ColumnType::Float => "float",
+                ColumnType::Date => "date",
+                ColumnType::Text => "text",
+            };
+            let _ = writeln!(out, "{col}: {kind}");
+        }
+        out.pop();
+        out
+    }
+}
+
+/// Parses a format-num pattern, which is an example number: `1,234.56`
+/// means thousands separators and two decimals, `1234` means neither.
+fn parse_number_pattern(pattern: &str) -> Result<(usize, bool), TransformError> {
+    if pattern.is_empty() || !pattern.chars().all(|c| c.is_ascii_digit() || c == ',' || c == '.')
+    {
+        return Err(TransformError::InvalidArguments(format!(
+            "invalid number pattern '{pattern}', expected an example like 1,234.56"
+        )));
+    }
+    let thousands = pattern.contains(',');
+    let decimals = pattern
+        .rsplit_once('.')
+        .map_or(0, |(_, fraction)| fraction.len());
+    Ok((decimals, thousands))
+}
+
+/// `value` with `decimals` fixed decimal places and, when asked for,
+/// comma-grouped integer digits.
+fn format_number(value: f64, decimals: usize, thousands: bool) -> String {
+    let fixed = format!("{value:.decimals$}");
+    if !thousands {
+        return fixed;
+    }
+    let (integer, fraction) = match fixed.split_once('.') {
+        Some((i, f)) => (i, Some(f)),
+        None => (fixed.as_str(), None),
+    };
+    let (sign, digits) = match integer.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", integer),
+    };
+
+    let mut grouped = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    match fraction {
+        Some(fraction) => format!("{sign}{grouped}.{fraction}"),
+        None => format!("{sign}{grouped}"),
+    }
+}
+
+/// Parses `rows=<col>,cols=<col>,val=<col>,agg=sum` for the pivot
+/// mode. The three columns are required; `agg` defaults to sum.
+fn parse_pivot_spec(spec: &str) -> Result<(String, String, String, Agg), TransformError> {
+    let mut rows = None;
+    let mut cols = None;
+    let mut val = None;
+    let mut agg = Agg::Sum;
+    for part in spec.split(',') {
+        let (key, value) = part.split_once('=').ok_or_else(|| {
+            TransformError::InvalidArguments(format!(
+                "pivot expects key=value parts, got '{part}'"
+            ))
+        })?;
+        match key {
+            "rows" => rows = Some(value.to_string()),
+            "cols" => cols = Some(value.to_string()),
+            "val" => val = Some(value.to_string()),
+            "agg" => agg = value.parse()?,
+            other => {
+                return Err(TransformError::InvalidArguments(format!(
+                    "unknown pivot part '{other}', expected rows, cols, val, or agg"
+                )))
+            }
+        }
+    }
+    match (rows, cols, val) {
+        (Some(rows), Some(cols), Some(val)) => Ok((rows, cols, val, agg)),
+        _ => Err(TransformError::InvalidArguments(
+            "pivot requires rows=<col>,cols=<col>,val=<col>".to_string(),
+        )),
+    }
+}
+
+/// Two decimals with trailing zeros (and a bare point) trimmed, so `6`
+/// stays `6` but a fractional average keeps its precision.
+fn format_agg(value: f64) -> String {
+    let rounded = format!("{value:.2}");
+    rounded.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+fn total_width(widths: &[usize]) -> usize {
+    widths.iter().map(|w| w + 3).sum::<usize>() + 1
+}
+
+/// How many leading columns fit within `limit` once a one-wide `…`
+/// indicator column is reserved. Always at least one, so even absurdly
+/// narrow limits still show something.
+fn fitting_columns(widths: &[usize], limit: usize) -> usize {
+    let mut used = 1 + 4; // leading separator plus the indicator column
+    let mut fit = 0;
+    for width in widths {
+        if fit > 0 && used + width + 3 > limit {
+            break;
+        }
+        used += width + 3;
+        fit += 1;
+    }
+    fit
+}
+
+/// Greedy word wrap used for captions; words longer than the width get
+/// a line of their own.
+fn wrap_words(text: &str, width: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        if current.is_empty() {
+            current = word.to_string();
+        } else if display_width(&current) + 1 + display_width(word) <= width {
+            current.push(' ');
+            current.push_str(word);
+        } else {
+            lines.push(std::mem::take(&mut current));
+            current = word.to_string();
+        }
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+/// Matches `YYYY-MM-DD`-shaped cells without pulling in a date crate.
+fn looks_like_date(cell: &str) -> bool {
+    let bytes = cell.as_bytes();
+    bytes.len() == 10
+        && bytes[4] == b'-'
+        && bytes[7] == b'-'
+        && bytes
+            .iter()
+            .enumerate()
+            .all(|(i, b)| i == 4 || i == 7 || b.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "name,age,joined\nAlice,30,2021-04-01\nBob,25,2022-11-15";
+
+    fn parsed() -> Csv {
+        parse_csv_data(SAMPLE, b',').unwrap()
+    }
+
+    #[test]
+    fn parses_headers_and_rows() {
+        let csv = parsed();
+        assert_eq!(csv.columns, vec!["name", "age", "joined"]);
+        assert_eq!(csv.rows.len(), 2);
+        assert_eq!(csv.rows[0][0], "Alice");
+    }
+
+    #[test]
+    fn sort_columns_keeps_cells_with_their_headers() {
+        let mut csv = parse_csv_data("charlie,alpha,bravo\nc1,a1,b1\nc2,a2,b2", b',').unwrap();
+        csv.sort_columns();
+        assert_eq!(csv.columns, vec!["alpha", "bravo", "charlie"]);
+        assert_eq!(csv.rows[0], vec!["a1", "b1", "c1"]);
+        assert_eq!(csv.rows[1], vec!["a2", "b2", "c2"]);
+    }
+
+    #[test]
+    fn detects_semicolon_delimiter() {
+        assert_eq!(detect_delimiter("a;b;c\n1;2;3"), b';');
+    }
+
+    #[test]
+    fn table_contains_all_cells() {
+        let table = parsed().format_as_table(&TableOptions::default());
+        for cell in ["name", "Alice", "Bob", "30", "25"] {
+            assert!(table.contains(cell), "missing {cell} in:\n{table}");
+        }
+    }
+
+    #[test]
+    fn to_sql_quotes_and_escapes_values() {
+        let csv = parse_csv_data("name,age\nO'Brien,44\nAda,36", b',').unwrap();
+        assert_eq!(
+            csv.to_sql("people", false),
+            "INSERT INTO people (name, age) VALUES ('O''Brien', '44');\n\
+             INSERT INTO people (name, age) VALUES ('Ada', '36');"
+        );
+        assert_eq!(
+            csv.to_sql("people", true),
+            "INSERT INTO people (name, age) VALUES ('O''Brien', 44);\n\
+             INSERT INTO people (name, age) VALUES ('Ada', 36);"
+        );
+    }
+
+    #[test]
+    fn check_passes_clean_input_and_names_the_bad_line() {
+        let out = check_csv(&SubCommand::default(), SAMPLE.to_string()).unwrap();
+        assert_eq!(out, "ok: 2 rows, 3 columns");
+
+        let err = check_csv(&SubCommand::default(), "a,b\n1,2,3\n4,5".to_string()).unwrap_err();
+        assert!(
+            err.to_string().contains("line 2: unequal-lengths"),
+            "got: {err}"
+        );
+    }
+
+    #[test]
+    fn trim_cells_removes_padding_before_layout() {
+        let sub =
+            SubCommand::parse(&["trim-cells:true".to_string(), "f:csv".to_string()]).unwrap();
+        let out = process_csv(&sub, "name ,  age\n Alice\tB. ,  30 ".to_string()).unwrap();
+        assert_eq!(out, "name,age\nAlice B.,30");
+    }
+
+    #[test]
+    fn comment_lines_are_skipped_before_parsing() {
+        let data = "# generated 2024-01-01\nname,age\nAlice,30\n  # midway note\nBob,25";
+        let sub = SubCommand::parse(&["comment:#".to_string(), "f:csv".to_string()]).unwrap();
+        let out = process_csv(&sub, data.to_string()).unwrap();
+        assert_eq!(out, "name,age\nAlice,30\nBob,25");
+
+        // Without the option the comment is taken as the header.
+        let sub = SubCommand::parse(&["f:csv".to_string()]).unwrap();
+        let out = process_csv(&sub, data.to_string()).unwrap();
+        assert!(out.starts_with("# generated 2024-01-01"), "got: {out}");
+    }
+
+    #[test]
+    fn lossy_parse_collects_every_issue() {
+        let (csv, issues) = parse_csv_data_lossy("a,b\n1,2,3\n4\n5,6", b',').unwrap();
+        assert_eq!(csv.rows, vec![vec!["5", "6"]]);
+        assert_eq!(issues.len(), 2, "issues: {issues:?}");
+        assert_eq!(issues[0].line, 2);
+        assert_eq!(issues[1].line, 3);
+        assert!(issues.iter().all(|i| i.kind == "unequal-lengths"));
+    }
+
+    #[test]
+    fn errors_json_mode_renders_and_reports() {
+        let sub = SubCommand::parse(&["errors:json".to_string(), "f:csv".to_string()]).unwrap();
+        let out = process_csv(&sub, "a,b\n1,2,3\n4\n5,6".to_string()).unwrap();
+        let (rendered, json) = out.rsplit_once('\n').unwrap();
+        assert_eq!(rendered, "a,b\n5,6");
+        assert!(json.contains("\"line\":2") && json.contains("\"line\":3"), "got: {json}");
+    }
+
+    #[test]
+    fn col_sep_choices_apply_to_cells_and_borders() {
+        let csv = parse_csv_data("a,b\n1,2", b',').unwrap();
+        for sep in ['│', '┆', '|', ' '] {
+            let options = TableOptions {
+                col_sep: sep,
+                ..TableOptions::default()
+            };
+            let table = csv.format_as_table(&options);
+            let data_row = table.lines().find(|l| l.contains('1')).unwrap();
+            assert_eq!(data_row, format!("{sep} 1 {sep} 2 {sep}"));
+            let border = table.lines().next().unwrap();
+            assert_eq!(border, format!("{sep}┄┄┄{sep}┄┄┄{sep}"));
+        }
+    }
+
+    #[test]
+    fn zebra_stripes_odd_data_rows() {
+        let options = TableOptions {
+            zebra: true,
+            ..TableOptions::default()
+        };
+        let table = parsed().format_as_table(&options);
+
+        let alice = table.lines().find(|l| l.contains("Alice")).unwrap();
+        let bob = table.lines().find(|l| l.contains("Bob")).unwrap();
+        assert!(!alice.contains(ANSI_STRIPE), "row 0 striped:\n{table}");
+        assert!(bob.contains(ANSI_STRIPE), "row 1 not striped:\n{table}");
+    }
+
+    #[test]
+    fn caption_is_centered_above_the_table() {
+        let options = TableOptions {
+            caption: Some("People".to_string()),
+            ..TableOptions::default()
+        };
+        let table = parsed().format_as_table(&options);
+        let mut lines = table.lines();
+
+        let caption_line = lines.next().unwrap();
+        let border_line = lines.next().unwrap();
+        assert_eq!(caption_line.trim(), "People");
+        assert!(border_line.chars().all(|c| c == '┄' || c == '┆'));
+
+        let width = border_line.chars().count();
+        let leading = caption_line.len() - caption_line.trim_start().len();
+        assert_eq!(leading, (width - "People".len()) / 2);
+    }
+
+    #[test]
+    fn long_cells_are_truncated() {
+        let long = "x".repeat(MAX_CELL_WIDTH * 2);
+        let data = format!("col\n{long}");
+        let table = parse_csv_data(&data, b',').unwrap().format_as_table(&TableOptions::default());
+        assert!(table.contains('…'));
+        assert!(!table.contains(&long));
+    }
+
+    #[test]
+    fn rownum_prepends_a_numbered_column() {
+        let csv = parse_csv_data("name\nAlice\nBob\nCarol", b',').unwrap();
+        let options = TableOptions {
+            rownum: true,
+            ..TableOptions::default()
+        };
+        let table = csv.format_as_table(&options);
+
+        let header = table.lines().nth(1).unwrap();
+        assert!(header.starts_with("┆ # ┆"), "got: {header}");
+        for (i, name) in ["Alice", "Bob", "Carol"].iter().enumerate() {
+            let row = table.lines().find(|l| l.contains(name)).unwrap();
+            assert!(row.starts_with(&format!("┆ {} ┆", i + 1)), "got: {row}");
+        }
+    }
+
+    #[test]
+    fn narrow_limit_drops_trailing_columns_behind_an_indicator() {
+        let options = TableOptions {
+            max_width: Some(20),
+            ..TableOptions::default()
+        };
+        let table = parsed().format_as_table(&options);
+        assert!(!table.contains("joined"), "got:\n{table}");
+        assert!(table.contains("age"));
+        let header = table.lines().nth(1).unwrap();
+        assert!(header.ends_with("┆ … ┆"), "got: {header}");
+        for line in table.lines() {
+            assert!(line.chars().count() <= 20, "too wide: {line}");
+        }
+    }
+
+    #[test]
+    fn overflow_records_switches_to_the_record_view() {
+        let sub = SubCommand::parse(&[
+            "term-width:10".to_string(),
+            "overflow:records".to_string(),
+        ])
+        .unwrap();
+        let out = process_csv(&sub, SAMPLE.to_string()).unwrap();
+        assert!(out.starts_with("name: Alice"), "got:\n{out}");
+
+        // A limit the table fits in keeps the table view.
+        let sub = SubCommand::parse(&[
+            "term-width:80".to_string(),
+            "overflow:records".to_string(),
+        ])
+        .unwrap();
+        let out = process_csv(&sub, SAMPLE.to_string()).unwrap();
+        assert!(out.contains('┆'), "got:\n{out}");
+    }
+
+    #[test]
+    fn records_view_prints_header_value_blocks() {
+        let out = parsed().to_records();
+        assert_eq!(
+            out,
+            "name: Alice\nage: 30\njoined: 2021-04-01\n\nname: Bob\nage: 25\njoined: 2022-11-15"
+        );
+    }
+
+    #[test]
+    fn dialect_reports_a_quoted_comma_file() {
+        let data = "name,note\r\n\"Smith, Jane\",hi\r\n";
+        let out = dialect(&SubCommand::default(), data.to_string()).unwrap();
+        assert_eq!(
+            out,
+            "delimiter: ,\nquote: \"\nheader: yes\nline-endings: crlf"
+        );
+
+        let out = dialect(&SubCommand::default(), "1\t2\n3\t4\n".to_string()).unwrap();
+        assert_eq!(
+            out,
+            "delimiter: tab\nquote: none\nheader: no\nline-endings: lf"
+        );
+    }
+
+    #[test]
+    fn format_num_adds_thousands_separators_and_skips_junk() {
+        let sub = SubCommand::parse(&[
+            "format-num:amount:1,234".to_string(),
+            "f:csv".to_string(),
+            "d:,".to_string(),
+        ])
+        .unwrap();
+        let out = process_csv(&sub, "amount\n1234567\nn/a\n-9500".to_string()).unwrap();
+        assert_eq!(out, "amount\n\"1,234,567\"\nn/a\n\"-9,500\"");
+
+        assert_eq!(format_number(1234.5, 2, true), "1,234.50");
+        assert!(parse_number_pattern("abc").is_err());
+        assert_eq!(parse_number_pattern("1,234.56").unwrap(), (2, true));
+    }
+
+    #[test]
+    fn pivot_aggregates_a_sales_table() {
+        let data = "region,quarter,amount\n\
+                    north,q1,10\n\
+                    north,q1,5\n\
+                    north,q2,7\n\
+                    south,q1,3";
+        let csv = parse_csv_data(data, b',').unwrap();
+        let pivoted = csv.pivot("region", "quarter", "amount", Agg::Sum).unwrap();
+
+        assert_eq!(pivoted.columns, vec!["region", "q1", "q2"]);
+        assert_eq!(pivoted.rows[0], vec!["north", "15", "7"]);
+        // south never sold in q2: empty cell, not zero.
+        assert_eq!(pivoted.rows[1], vec!["south", "3", ""]);
+
+        let sub = SubCommand::parse(&[
+            "pivot:rows=region,cols=quarter,val=amount,agg=count".to_string(),
+            "f:csv".to_string(),
+            "d:,".to_string(),
+        ])
+        .unwrap();
+        let out = process_csv(&sub, data.to_string()).unwrap();
+        assert_eq!(out, "region,q1,q2\nnorth,2,1\nsouth,1,");
+
+        assert!(parse_pivot_spec("rows=region").is_err());
+        assert!(parse_pivot_spec("rows=a,cols=b,val=c,agg=median").is_err());
+    }
+
+    #[test]
+    fn footer_sums_numeric_columns_behind_a_heavy_border() {
+        let csv = parse_csv_data("item,qty\napples,2\npears,4", b',').unwrap();
+        let options = TableOptions {
+            footer: Some(Agg::Sum),
+            ..TableOptions::default()
+        };
+        let out = csv.format_as_table(&options);
+        let lines: Vec<&str> = out.lines().collect();
+        let footer = lines[lines.len() - 2];
+        assert!(footer.contains(" sum ") && footer.contains(" 6 "), "got:\n{out}");
+        assert!(lines[lines.len() - 3].contains('┅'), "got:\n{out}");
+
+        assert_eq!(csv.footer_row(Agg::Avg), vec!["avg".to_string(), "3".to_string()]);
+        assert_eq!(csv.footer_row(Agg::Count), vec!["count".to_string(), "2".to_string()]);
+    }
+
+    #[test]
+    fn collapse_merges_runs_of_identical_rows() {
+        let data = "state\nok\nok\nok\nerror\nok";
+        let sub = SubCommand::parse(&[
+            "collapse:true".to_string(),
+            "f:csv".to_string(),
+            "d:,".to_string(),
+        ])
+        .unwrap();
+        let out = process_csv(&sub, data.to_string()).unwrap();
+        assert_eq!(out, "state,count\nok,3\nerror,1\nok,1");
+    }
+
+    #[test]
+    fn repeat_header_reprints_the_band_every_n_rows() {
+        let data: String = std::iter::once("n".to_string())
+            .chain((1..=10).map(|i| i.to_string()))
+            .collect::<Vec<String>>()
+            .join("\n");
+        let csv = parse_csv_data(&data, b',').unwrap();
+
+        let options = TableOptions {
+            repeat_header: 5,
+            ..TableOptions::default()
+        };
+        let out = csv.format_as_table(&options);
+        let header_line = out.lines().nth(1).unwrap().to_string();
+        let bands = out.lines().filter(|line| *line == header_line).count();
+        assert_eq!(bands, 2, "got:\n{out}");
+
+        // Zero keeps the single header band.
+        let out = csv.format_as_table(&TableOptions::default());
+        assert_eq!(out.lines().filter(|line| *line == header_line).count(), 1);
+    }
+
+    #[test]
+    fn apply_runs_a_text_transform_over_one_column() {
+        let sub = SubCommand::parse(&["apply:name:uppercase".to_string(), "f:csv".to_string()])
+            .unwrap();
+        let out = process_csv(&sub, SAMPLE.to_string()).unwrap();
+        assert!(out.contains("ALICE") && out.contains("BOB"), "got:\n{out}");
+        assert!(out.contains("2021-04-01"), "other columns untouched:\n{out}");
+
+        assert!(parsed().apply_column("nope", "uppercase").is_err());
+        assert!(parsed().apply_column("name", "not-a-command").is_err());
+    }
+
+    #[test]
+    fn seeded_sample_is_deterministic_and_a_subset() {
+        let data = "n\n1\n2\n3\n4\n5\n6";
+        let csv = parse_csv_data(data, b',').unwrap();
+
+        let first = csv.sample(2, Some(42));
+        let second = csv.sample(2, Some(42));
+        assert_eq!(first, second);
+        assert_eq!(first.rows.len(), 2);
+        assert!(first.rows.iter().all(|row| csv.rows.contains(row)));
+
+        // Asking for more rows than exist keeps them all.
+        assert_eq!(csv.sample(10, Some(42)).rows, csv.rows);
+    }
+
+    #[test]
+    fn row_mode_prints_one_record_and_checks_the_range() {
+        let sub = SubCommand::parse(&["row:2".to_string()]).unwrap();
+        let out = process_csv(&sub, SAMPLE.to_string()).unwrap();
+        assert_eq!(out, "name: Bob\nage: 25\njoined: 2022-11-15");
+
+        let sub = SubCommand::parse(&["row:3".to_string()]).unwrap();
+        assert!(process_csv(&sub, SAMPLE.to_string()).is_err());
+        assert!(parsed().row_record(0).is_err());
+    }
+
+    #[test]
+    fn yaml_output_parses_back_into_the_same_rows() {
+        let yaml = parsed().to_yaml(true).unwrap();
+        let values: Vec<serde_yaml::Value> = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[0]["name"], serde_yaml::Value::from("Alice"));
+        assert_eq!(values[0]["age"], serde_yaml::Value::from(30));
+
+        let quoted = parsed().to_yaml(false).unwrap();
+        let reparsed: Vec<serde_yaml::Value> = serde_yaml::from_str(&quoted).unwrap();
+        assert_eq!(reparsed[1]["age"], serde_yaml::Value::from("25"));
+    }
+
+    #[test]
+    fn round_trips_through_delimited() {
+        let csv = parsed();
+        let reparsed = parse_csv_data(&csv.to_delimited(','), b',').unwrap();
+        assert_eq!(csv, reparsed);
+    }
+
+    #[test]
+    fn column_stats_over_five_numbers() {
+        let csv = parse_csv_data("n\n4\n2\n8\n1\n5", b',').unwrap();
+        let stats = csv.column_stats("n").unwrap();
+        assert_eq!(stats.count, 5);
+        assert_eq!(stats.ignored, 0);
+        assert_eq!(stats.min, 1.0);
+        assert_eq!(stats.max, 8.0);
+        assert_eq!(stats.sum, 20.0);
+        assert_eq!(stats.mean, 4.0);
+        assert_eq!(stats.median, 4.0);
+    }
+
+    #[test]
+    fn column_stats_counts_ignored_and_handles_empty() {
+        let csv = parse_csv_data("n,k\n3,a\nx,b\n,c\n7,d", b',').unwrap();
+        let stats = csv.column_stats("n").unwrap();
+        assert_eq!(stats.count, 2);
+        assert_eq!(stats.ignored, 2);
+
+        let empty = parse_csv_data("n\nfoo\nbar", b',').unwrap();
+        let stats = empty.column_stats("n").unwrap();
+        assert_eq!(stats.count, 0);
+        assert!(stats.mean.is_nan());
+        assert_eq!(stats.sum, 0.0);
+    }
+
+    #[test]
+    fn column_stats_unknown_column_errors() {
+        let csv = parse_csv_data(SAMPLE, b',').unwrap();
+        assert!(csv.column_stats("salary").is_err());
+    }
+
+    #[test]
+    fn dupes_reports_repeated_rows_and_key_values() {
+        let data = "name,city\nAlice,Oslo\nBob,Bergen\nAlice,Oslo\nCarol,Oslo";
+        let csv = parse_csv_data(data, b',').unwrap();
+
+        assert_eq!(
+            csv.find_duplicates(None).unwrap(),
+            vec![("Alice,Oslo".to_string(), 2)]
+        );
+        assert_eq!(
+            csv.find_duplicates(Some("city")).unwrap(),
+            vec![("Oslo".to_string(), 3)]
+        );
+        assert!(csv.find_duplicates(Some("country")).is_err());
+
+        let sub = SubCommand::parse(&["dupes:true".to_string()]).unwrap();
+        let out = process_csv(&sub, data.to_string()).unwrap();
+        assert_eq!(out, "Alice,Oslo: 2");
+
+        let sub = SubCommand::parse(&["dupes:true".to_string(), "key:name".to_string()]).unwrap();
+        let out = process_csv(&sub, "name\nAda\nGrace".to_string()).unwrap();
+        assert_eq!(out, "no duplicates");
+    }
+
+    #[test]
+    fn split_by_writes_one_file_per_category() {
+        let csv = parse_csv_data(
+            "name,team\nAlice,Red Pandas\nBob,Blue\nCarol,Red Pandas",
+            b',',
+        )
+        .unwrap();
+        let outdir = std::env::temp_dir().join("hw07_split_test");
+
+        let summary = csv.split_by("team", &outdir, ',').unwrap();
+        assert_eq!(summary.lines().count(), 2);
+
+        let red = fs::read_to_string(outdir.join("red-pandas.csv")).unwrap();
+        assert_eq!(red, "name,team\nAlice,Red Pandas\nCarol,Red Pandas");
+        let blue = fs::read_to_string(outdir.join("blue.csv")).unwrap();
+        assert_eq!(blue, "name,team\nBob,Blue");
+
+        assert!(csv.split_by("color", &outdir, ',').is_err());
+        fs::remove_dir_all(&outdir).unwrap();
+    }
+
+    #[test]
+    fn infers_column_types() {
+        let csv = parsed();
+        assert_eq!(csv.infer_column_type(0), ColumnType::Text);
+        assert_eq!(csv.infer_column_type(1), ColumnType::Integer);
+        assert_eq!(csv.infer_column_type(2), ColumnType::Date);
+    }
+
+    #[test]
+    fn details_mode_folds_the_markdown_table() {
+        let sub = SubCommand::parse(&[
+            "d:,".to_string(),
+            "f:details".to_string(),
+            "summary:People".to_string(),
+        ])
+        .unwrap();
+        let out = process_csv(&sub, SAMPLE.to_string()).unwrap();
+        assert!(out.starts_with("<details><summary>People</summary>\n\n| name | age | joined |"));
+        assert!(out.contains("| Alice | 30 | 2021-04-01 |"));
+        assert!(out.ends_with("\n\n</details>"));
+
+        let sub = SubCommand::parse(&["d:,".to_string(), "f:details".to_string()]).unwrap();
+        let out = process_csv(&sub, SAMPLE.to_string()).unwrap();
+        assert!(out.contains("<summary>2 rows</summary>"));
+    }
+
+    #[test]
+    fn type_icons_prefix_headers_with_inferred_types() {
+        let mut csv = parsed();
+        csv.decorate_headers_with_types(false);
+        assert_eq!(csv.columns, vec!["A name", "# age", "D joined"]);
+
+        let mut csv = parsed();
+        csv.decorate_headers_with_types(true);
+        assert_eq!(csv.columns[2], "📅 joined");
+    }
+}
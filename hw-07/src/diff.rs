@@ -0,0 +1,119 @@
+use std::fs;
+
+use similar::{ChangeTag, TextDiff};
+
+use crate::text_utils::{SubCommand, TransformError};
+
+/// Line diff between stdin and the file given by `p:<path>`, in a
+/// unified-ish `-`/`+` format.
+pub fn diff(sub: &SubCommand, input: &str) -> Result<String, TransformError> {
+    let path = sub.get("p").ok_or_else(|| {
+        TransformError::InvalidArguments("diff requires p:<path> to compare against".to_string())
+    })?;
+    let other = fs::read_to_string(path)?;
+
+    let diff = TextDiff::from_lines(input, &other);
+    let mut out = String::new();
+    for change in diff.iter_all_changes() {
+        let sign = match change.tag() {
+            ChangeTag::Delete => "-",
+            ChangeTag::Insert => "+",
+            ChangeTag::Equal => " ",
+        };
+        out.push_str(sign);
+        out.push_str(change.value());
+        if !change.value().ends_with('\n') {
+            out.push('\n');
+        }
+    }
+    out.pop();
+    Ok(out)
+}
+
+/// Before→after view of what a transform did, for `--show-changes`:
+/// each changed line appears as its `- ` original and `+ ` replacement.
+/// Unchanged lines are omitted unless `all` is set.
+pub fn render_changes(before: &str, after: &str, all: bool) -> String {
+    let diff = TextDiff::from_lines(before, after);
+    let mut out = String::new();
+    for change in diff.iter_all_changes() {
+        let sign = match change.tag() {
+            ChangeTag::Delete => "- ",
+            ChangeTag::Insert => "+ ",
+            ChangeTag::Equal if all => "  ",
+            ChangeTag::Equal => continue,
+        };
+        out.push_str(sign);
+        out.push_str(change.value());
+        if !change.value().ends_with('\n') {
+            out.push('\n');
+        }
+    }
+    out.pop();
+    out
+}
+
+/// Word-granularity diff between stdin and the file given by `p:<path>`,
+/// with inline `[-removed-]{+added+}` markers. Whitespace-only changes
+/// are applied silently rather than marked, so spacing tweaks do not
+/// drown out the real edits.
+pub fn word_diff(sub: &SubCommand, input: &str) -> Result<String, TransformError> {
+    let path = sub.get("p").ok_or_else(|| {
+        TransformError::InvalidArguments(
+            "word-diff requires p:<path> to compare against".to_string(),
+        )
+    })?;
+    let other = fs::read_to_string(path)?;
+
+    let diff = TextDiff::from_words(input, &other);
+    let mut out = String::new();
+    for change in diff.iter_all_changes() {
+        let value = change.value();
+        match change.tag() {
+            ChangeTag::Delete if value.trim().is_empty() => {}
+            ChangeTag::Delete => {
+                out.push_str("[-");
+                out.push_str(value);
+                out.push_str("-]");
+            }
+            ChangeTag::Insert if value.trim().is_empty() => out.push_str(value),
+            ChangeTag::Insert => {
+                out.push_str("{+");
+                out.push_str(value);
+                out.push_str("+}");
+            }
+            ChangeTag::Equal => out.push_str(value),
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn show_changes_pairs_original_and_transformed_lines() {
+        // What `--show-changes` prints after an uppercase transform.
+        let before = "keep this\nchange me";
+        let after = "keep this\nCHANGE ME";
+        assert_eq!(render_changes(before, after, false), "- change me\n+ CHANGE ME");
+        assert_eq!(
+            render_changes(before, after, true),
+            "  keep this\n- change me\n+ CHANGE ME"
+        );
+    }
+
+    #[test]
+    fn word_diff_marks_changed_words_inline() {
+        let path = std::env::temp_dir().join("hw07_word_diff_test.txt");
+        fs::write(&path, "the quick red fox jumps").unwrap();
+
+        let sub =
+            SubCommand::parse(&[format!("p:{}", path.display())]).unwrap();
+        let out = word_diff(&sub, "the quick brown fox jumps").unwrap();
+        fs::remove_file(&path).ok();
+
+        assert_eq!(out, "the quick [-brown-]{+red+} fox jumps");
+    }
+}
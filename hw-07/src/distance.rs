@@ -0,0 +1,52 @@
+use std::fs;
+
+use crate::text_utils::{SubCommand, TransformError};
+
+/// Levenshtein edit distance between stdin and either a file
+/// (`p:<path>`) or a literal (`to:<text>`). With `norm:true` the output
+/// is a 0.0–1.0 similarity instead, 1.0 meaning identical.
+pub fn distance(sub: &SubCommand, input: &str) -> Result<String, TransformError> {
+    let other = match (sub.get("p"), sub.get("to")) {
+        (Some(_), Some(_)) => {
+            return Err(TransformError::InvalidArguments(
+                "distance takes p:<path> or to:<text>, not both".to_string(),
+            ))
+        }
+        (Some(path), None) => fs::read_to_string(path)?,
+        (None, Some(text)) => text.to_string(),
+        (None, None) => {
+            return Err(TransformError::InvalidArguments(
+                "distance requires p:<path> or to:<text>".to_string(),
+            ))
+        }
+    };
+
+    if sub.get_bool("norm") {
+        Ok(format!(
+            "{:.3}",
+            strsim::normalized_levenshtein(input, &other)
+        ))
+    } else {
+        Ok(strsim::levenshtein(input, &other).to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identical_strings_have_distance_zero() {
+        let sub = SubCommand::parse(&["to:kitten".to_string()]).unwrap();
+        assert_eq!(distance(&sub, "kitten").unwrap(), "0");
+
+        let sub = SubCommand::parse(&["to:kitten".to_string(), "norm:true".to_string()]).unwrap();
+        assert_eq!(distance(&sub, "kitten").unwrap(), "1.000");
+    }
+
+    #[test]
+    fn known_edit_distance() {
+        let sub = SubCommand::parse(&["to:sitting".to_string()]).unwrap();
+        assert_eq!(distance(&sub, "kitten").unwrap(), "3");
+    }
+}
@@ -0,0 +1,112 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::text_utils::{SubCommand, TransformError};
+
+pub static EMAIL_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}").expect("valid email regex")
+});
+
+pub static URL_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"https?://[^\s<>\x22]+").expect("valid URL regex"));
+
+/// Pulls out all emails or URLs from the input, one match per line.
+/// Selected with `kind:email` or `kind:url`.
+pub fn extract(sub: &SubCommand, input: &str) -> Result<String, TransformError> {
+    let re = regex_for_kind(sub.get("kind"))?;
+    let matches: Vec<&str> = re.find_iter(input).map(|m| m.as_str()).collect();
+    Ok(matches.join("\n"))
+}
+
+/// Cleans a one-per-line list of emails or URLs: entries that are not a
+/// single valid value of the chosen `kind` are reported on stderr and
+/// dropped, the domain part of the survivors is lowercased, and the
+/// result is deduplicated and sorted.
+pub fn normalize_list(sub: &SubCommand, input: &str) -> Result<String, TransformError> {
+    let kind = sub.get("kind");
+    let re = regex_for_kind(kind)?;
+
+    let mut clean = std::collections::BTreeSet::new();
+    for line in input.lines() {
+        let entry = line.trim();
+        if entry.is_empty() {
+            continue;
+        }
+        // Canonicalize before validating so an uppercase scheme like
+        // `HTTPS://` is fixed rather than rejected.
+        let canonical = lowercase_domain(entry, kind == Some("email"));
+        let valid = re.find(&canonical).is_some_and(|m| m.as_str() == canonical);
+        if !valid {
+            eprintln!("normalize-list: invalid entry '{entry}'");
+            continue;
+        }
+        clean.insert(canonical);
+    }
+    Ok(clean.into_iter().collect::<Vec<String>>().join("\n"))
+}
+
+/// Lowercases the case-insensitive part of an entry: everything after
+/// the last `@` for an email, the scheme and host (up to the first `/`
+/// after `://`) for a URL. The local part and path keep their case.
+fn lowercase_domain(entry: &str, email: bool) -> String {
+    if email {
+        match entry.rsplit_once('@') {
+            Some((local, domain)) => format!("{local}@{}", domain.to_lowercase()),
+            None => entry.to_string(),
+        }
+    } else {
+        let host_end = entry
+            .find("://")
+            .map(|i| entry[i + 3..].find('/').map_or(entry.len(), |j| i + 3 + j))
+            .unwrap_or(entry.len());
+        format!(
+            "{}{}",
+            entry[..host_end].to_lowercase(),
+            &entry[host_end..]
+        )
+    }
+}
+
+pub fn regex_for_kind(kind: Option<&str>) -> Result<&'static Regex, TransformError> {
+    match kind {
+        Some("email") => Ok(&EMAIL_RE),
+        Some("url") => Ok(&URL_RE),
+        Some(other) => Err(TransformError::InvalidArguments(format!(
+            "unknown kind '{other}', expected email or url"
+        ))),
+        None => Err(TransformError::InvalidArguments(
+            "extract requires kind:email or kind:url".to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_emails() {
+        let sub = SubCommand::parse(&["kind:email".to_string()]).unwrap();
+        let out = extract(&sub, "mail a@b.com and c.d@e.org today").unwrap();
+        assert_eq!(out, "a@b.com\nc.d@e.org");
+    }
+
+    #[test]
+    fn normalize_list_dedupes_sorts_and_drops_invalid_entries() {
+        let sub = SubCommand::parse(&["kind:email".to_string()]).unwrap();
+        let input = "Bob@Example.COM\nnot-an-email\nalice@example.com\nBob@example.com\n";
+        let out = normalize_list(&sub, input).unwrap();
+        assert_eq!(out, "Bob@example.com\nalice@example.com");
+
+        let sub = SubCommand::parse(&["kind:url".to_string()]).unwrap();
+        let out = normalize_list(&sub, "HTTPS://Example.com/KeepCase\n").unwrap();
+        assert_eq!(out, "https://example.com/KeepCase");
+    }
+
+    #[test]
+    fn extracts_urls() {
+        let sub = SubCommand::parse(&["kind:url".to_string()]).unwrap();
+        let out = extract(&sub, "see https://example.com/x and text").unwrap();
+        assert_eq!(out, "https://example.com/x");
+    }
+}
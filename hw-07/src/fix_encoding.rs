@@ -0,0 +1,50 @@
+/// Repairs the most common mojibake: UTF-8 bytes that were decoded as
+/// Latin-1/Windows-1252, turning `é` into `Ã©`. The fix re-encodes the
+/// text back to Windows-1252 bytes and decodes them as UTF-8, applied
+/// only when the input actually looks mangled and the round trip yields
+/// valid UTF-8 — clean text passes through untouched.
+///
+/// This is a heuristic. Text that legitimately contains `Ã`, `Â`, or
+/// `â` directly followed by another high Latin-1 character (rare
+/// outside mojibake) will be "fixed" too, and encodings other than the
+/// Latin-1 family are not detected.
+pub fn demojibake(input: &str) -> String {
+    if !looks_mangled(input) {
+        return input.to_string();
+    }
+    let (bytes, _, had_errors) = encoding_rs::WINDOWS_1252.encode(input);
+    if had_errors {
+        return input.to_string();
+    }
+    match std::str::from_utf8(&bytes) {
+        Ok(fixed) => fixed.to_string(),
+        Err(_) => input.to_string(),
+    }
+}
+
+/// UTF-8 multibyte sequences misread as Windows-1252 always start with
+/// `Â`, `Ã`, or `â` (bytes 0xC2, 0xC3, 0xE2) followed by another
+/// non-ASCII character.
+fn looks_mangled(input: &str) -> bool {
+    let chars: Vec<char> = input.chars().collect();
+    chars
+        .windows(2)
+        .any(|pair| matches!(pair[0], 'Â' | 'Ã' | 'â') && pair[1] as u32 >= 0x80)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixes_latin1_misread_utf8() {
+        assert_eq!(demojibake("CafÃ© crÃ¨me"), "Café crème");
+        assert_eq!(demojibake("donâ€™t"), "don’t");
+    }
+
+    #[test]
+    fn clean_text_passes_through() {
+        assert_eq!(demojibake("Café crème"), "Café crème");
+        assert_eq!(demojibake("plain ascii"), "plain ascii");
+    }
+}
@@ -0,0 +1,67 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Writes `contents` to `path` atomically: the bytes go to a temporary
+/// file in the same directory first and are renamed into place, so a
+/// crash or concurrent reader never sees a half-written file.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let file_name = path.file_name().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} has no file name", path.display()),
+        )
+    })?;
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    // Same directory as the target, so the rename stays on one filesystem.
+    let tmp = dir.join(format!(
+        ".{}.tmp.{}",
+        file_name.to_string_lossy(),
+        std::process::id()
+    ));
+
+    let result = (|| {
+        let mut file = fs::File::create(&tmp)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        fs::rename(&tmp, path)
+    })();
+    if result.is_err() {
+        fs::remove_file(&tmp).ok();
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn atomic_write_replaces_the_file_completely() {
+        let path = std::env::temp_dir().join("hw07_atomic_write_test.txt");
+        fs::write(&path, "old content that is longer than the new one").unwrap();
+
+        write_atomic(&path, b"new").unwrap();
+        let read_back = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).ok();
+
+        assert_eq!(read_back, "new");
+    }
+
+    #[test]
+    fn no_temporary_file_is_left_behind() {
+        let dir = std::env::temp_dir();
+        let path = dir.join("hw07_atomic_write_tmp_test.txt");
+        write_atomic(&path, b"payload").unwrap();
+        fs::remove_file(&path).ok();
+
+        let tmp = dir.join(format!(
+            ".hw07_atomic_write_tmp_test.txt.tmp.{}",
+            std::process::id()
+        ));
+        assert!(!tmp.exists());
+    }
+}
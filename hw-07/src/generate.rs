@@ -0,0 +1,124 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng as _;
+use sha2::{Digest as _, Sha256};
+
+use crate::text_utils::{SubCommand, TransformError};
+
+/// Words per paragraph when generating with `paras:<n>`.
+const PARAGRAPH_WORDS: usize = 60;
+
+/// Generates placeholder text: `words:<n>` for a single run of words,
+/// `paras:<n>` for blank-line-separated paragraphs. `seed:<n>` makes the
+/// output reproducible. Stdin is ignored.
+pub fn lorem(sub: &SubCommand) -> Result<String, TransformError> {
+    let words = sub.get_parsed::<usize>("words")?;
+    let paras = sub.get_parsed::<usize>("paras")?;
+    let seed = sub.get_parsed::<u64>("seed")?;
+
+    match (words, paras) {
+        (Some(_), Some(_)) => Err(TransformError::InvalidArguments(
+            "lorem takes words:<n> or paras:<n>, not both".to_string(),
+        )),
+        (Some(n), None) => Ok(generate(n, seed)),
+        (None, paras) => {
+            let paras = paras.unwrap_or(1);
+            let blocks: Vec<String> = (0..paras)
+                .map(|i| generate(PARAGRAPH_WORDS, seed.map(|s| s + i as u64)))
+                .collect();
+            Ok(blocks.join("\n\n"))
+        }
+    }
+}
+
+fn generate(words: usize, seed: Option<u64>) -> String {
+    match seed {
+        Some(seed) => lipsum::lipsum_with_rng(StdRng::seed_from_u64(seed), words),
+        None => lipsum::lipsum(words),
+    }
+}
+
+/// RFC 4648 base32 alphabet, lowercased: ids stay readable and are safe
+/// in URLs and filenames.
+const BASE32_ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+/// Default `gen-id` length in characters.
+const ID_LEN: usize = 12;
+
+/// Derives a short, deterministic id from the input: the SHA-256 digest
+/// rendered as base32 and truncated to `len:<n>` characters (default
+/// 12). The same input always yields the same id.
+pub fn gen_id(sub: &SubCommand, input: &str) -> Result<String, TransformError> {
+    let len = sub.get_parsed::<usize>("len")?.unwrap_or(ID_LEN);
+    let encoded = base32(&Sha256::digest(input.as_bytes()));
+    if len == 0 || len > encoded.len() {
+        return Err(TransformError::InvalidArguments(format!(
+            "len must be between 1 and {}",
+            encoded.len()
+        )));
+    }
+    Ok(encoded[..len].to_string())
+}
+
+/// Plain base32 without padding: five bits per output character.
+fn base32(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() * 8 / 5 + 1);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for &byte in bytes {
+        buffer = (buffer << 8) | u32::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(BASE32_ALPHABET[(buffer >> bits & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        out.push(BASE32_ALPHABET[(buffer << (5 - bits) & 0x1f) as usize] as char);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn words_count_is_exact() {
+        let sub = SubCommand::parse(&["words:10".to_string()]).unwrap();
+        let out = lorem(&sub).unwrap();
+        assert_eq!(out.split_whitespace().count(), 10, "got: {out}");
+    }
+
+    #[test]
+    fn paras_yields_blank_line_separated_blocks() {
+        let sub = SubCommand::parse(&["paras:3".to_string()]).unwrap();
+        let out = lorem(&sub).unwrap();
+        assert_eq!(out.split("\n\n").count(), 3);
+    }
+
+    #[test]
+    fn seed_makes_output_reproducible() {
+        let sub = SubCommand::parse(&["words:12".to_string(), "seed:7".to_string()]).unwrap();
+        assert_eq!(lorem(&sub).unwrap(), lorem(&sub).unwrap());
+    }
+
+    #[test]
+    fn gen_id_is_deterministic_per_input() {
+        let sub = SubCommand::default();
+        let first = gen_id(&sub, "hello world").unwrap();
+        assert_eq!(first, gen_id(&sub, "hello world").unwrap());
+        assert_eq!(first.len(), ID_LEN);
+        assert_ne!(first, gen_id(&sub, "hello world!").unwrap());
+    }
+
+    #[test]
+    fn gen_id_len_truncates_the_encoded_digest() {
+        let sub = SubCommand::parse(&["len:6".to_string()]).unwrap();
+        let id = gen_id(&sub, "hello world").unwrap();
+        assert_eq!(id.len(), 6);
+        assert!(gen_id(&sub, "x").unwrap().chars().all(|c| BASE32_ALPHABET.contains(&(c as u8))));
+
+        let sub = SubCommand::parse(&["len:0".to_string()]).unwrap();
+        assert!(gen_id(&sub, "x").is_err());
+    }
+}
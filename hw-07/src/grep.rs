@@ -0,0 +1,52 @@
+use regex::Regex;
+
+use crate::text_utils::{SubCommand, TransformError};
+
+/// Counts input lines matching `pattern:<regex>`. With `show:true` the
+/// matching lines follow the count, one per line.
+pub fn grep_count(sub: &SubCommand, input: &str) -> Result<String, TransformError> {
+    let pattern = sub.get("pattern").ok_or_else(|| {
+        TransformError::InvalidArguments("grep-count requires pattern:<regex>".to_string())
+    })?;
+    let re = Regex::new(pattern)
+        .map_err(|e| TransformError::InvalidArguments(format!("invalid regex: {e}")))?;
+
+    let matching: Vec<&str> = input.lines().filter(|line| re.is_match(line)).collect();
+    let mut out = matching.len().to_string();
+    if sub.get_bool("show") {
+        for line in matching {
+            out.push('\n');
+            out.push_str(line);
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_lines_matching_a_digit_pattern() {
+        let sub = SubCommand::parse(&[r"pattern:\d+".to_string()]).unwrap();
+        let out = grep_count(&sub, "alpha 1\nbeta\ngamma 22\ndelta").unwrap();
+        assert_eq!(out, "2");
+    }
+
+    #[test]
+    fn show_appends_the_matching_lines() {
+        let sub = SubCommand::parse(&[r"pattern:\d+".to_string(), "show:true".to_string()])
+            .unwrap();
+        let out = grep_count(&sub, "alpha 1\nbeta\ngamma 22").unwrap();
+        assert_eq!(out, "2\nalpha 1\ngamma 22");
+    }
+
+    #[test]
+    fn invalid_regex_is_rejected_up_front() {
+        let sub = SubCommand::parse(&["pattern:(".to_string()]).unwrap();
+        assert!(matches!(
+            grep_count(&sub, "anything"),
+            Err(TransformError::InvalidArguments(_))
+        ));
+    }
+}
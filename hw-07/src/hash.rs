@@ -0,0 +1,34 @@
+use md5::Md5;
+use sha2::{Digest, Sha256};
+
+use crate::text_utils::{SubCommand, TransformError};
+
+/// Hashes the input with the algorithm given by `algo:<name>`
+/// (sha256 by default) and prints the hex digest.
+pub fn hash(sub: &SubCommand, input: &str) -> Result<String, TransformError> {
+    match sub.get("algo").unwrap_or("sha256") {
+        "sha256" => Ok(to_hex(&Sha256::digest(input.as_bytes()))),
+        "md5" => Ok(to_hex(&Md5::digest(input.as_bytes()))),
+        other => Err(TransformError::InvalidArguments(format!(
+            "unknown hash algorithm '{other}', expected sha256 or md5"
+        ))),
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sha256_of_empty_string() {
+        let out = hash(&SubCommand::default(), "").unwrap();
+        assert_eq!(
+            out,
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+}
@@ -0,0 +1,58 @@
+use crate::text_utils::{SubCommand, TransformError};
+
+/// Loads the image at `p:<path>` and reports its format, dimensions,
+/// and color type without writing anything.
+pub fn image_info(sub: &SubCommand) -> Result<String, TransformError> {
+    let path = sub.get("p").ok_or_else(|| {
+        TransformError::InvalidArguments("imageinfo requires p:<path>".to_string())
+    })?;
+
+    let reader = image::io::Reader::open(path)?
+        .with_guessed_format()
+        .map_err(TransformError::Io)?;
+    let format = reader
+        .format()
+        .map(|f| format!("{f:?}"))
+        .unwrap_or_else(|| "unknown".to_string());
+    let image = reader
+        .decode()
+        .map_err(|e| TransformError::Other(format!("failed to decode {path}: {e}")))?;
+
+    Ok(format!(
+        "format: {format}\ndimensions: {}x{}\ncolor: {:?}",
+        image.width(),
+        image.height(),
+        image.color()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_dimensions_of_a_png() {
+        let path = std::env::temp_dir().join("hw07_imageinfo_test.png");
+        image::RgbImage::new(3, 2).save(&path).unwrap();
+
+        let sub =
+            SubCommand::parse(&[format!("p:{}", path.display())]).unwrap();
+        let out = image_info(&sub).unwrap();
+        assert!(out.contains("format: Png"), "got: {out}");
+        assert!(out.contains("dimensions: 3x2"), "got: {out}");
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn corrupt_file_errors_cleanly() {
+        let path = std::env::temp_dir().join("hw07_imageinfo_bogus.png");
+        std::fs::write(&path, b"not an image").unwrap();
+
+        let sub =
+            SubCommand::parse(&[format!("p:{}", path.display())]).unwrap();
+        assert!(image_info(&sub).is_err());
+
+        std::fs::remove_file(path).ok();
+    }
+}
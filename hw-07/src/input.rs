@@ -0,0 +1,290 @@
+use std::io::{self, Read};
+use std::str::FromStr;
+
+use crate::text_utils::{Command, SubCommand, TransformError};
+
+/// Parsed command-line invocation: which command to run (if any) and
+/// its `key:value` arguments. No command means interactive mode.
+#[derive(Debug, Default)]
+pub struct CliOptions {
+    pub command: Option<Command>,
+    pub sub: SubCommand,
+    /// Report how long each transform took on stderr.
+    pub time: bool,
+    /// Wrap results (and errors) in a JSON envelope on stdout.
+    pub json: bool,
+    /// Read the input from the system clipboard instead of stdin.
+    pub clipboard: bool,
+    /// Also copy the result back to the system clipboard.
+    pub to_clipboard: bool,
+    /// Also write the result to this file.
+    pub output: Option<std::path::PathBuf>,
+    /// Re-run the transform on this file whenever it changes.
+    pub watch: Option<std::path::PathBuf>,
+    /// Print a before/after of each changed line on stderr.
+    pub show_changes: bool,
+    /// With `--show-changes`, include unchanged lines too.
+    pub all: bool,
+    /// Print the reproducing command line instead of running.
+    pub explain: bool,
+}
+
+/// Where oneshot input comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Stdin,
+    Clipboard,
+}
+
+/// Picks the input source from the `--clipboard` flag. Factored out so
+/// the selection is testable without a real clipboard.
+pub fn select_source(clipboard: bool) -> Source {
+    if clipboard {
+        Source::Clipboard
+    } else {
+        Source::Stdin
+    }
+}
+
+pub fn read_clipboard() -> Result<String, TransformError> {
+    let mut clipboard = arboard::Clipboard::new()
+        .map_err(|e| TransformError::Other(format!("clipboard unavailable: {e}")))?;
+    clipboard
+        .get_text()
+        .map_err(|e| TransformError::Other(format!("failed to read clipboard: {e}")))
+}
+
+pub fn write_clipboard(text: &str) -> Result<(), TransformError> {
+    let mut clipboard = arboard::Clipboard::new()
+        .map_err(|e| TransformError::Other(format!("clipboard unavailable: {e}")))?;
+    clipboard
+        .set_text(text)
+        .map_err(|e| TransformError::Other(format!("failed to write clipboard: {e}")))
+}
+
+pub fn parse_args(args: &[String]) -> Result<CliOptions, TransformError> {
+    let mut options = CliOptions::default();
+    let mut ignore_case = false;
+    let mut positional: Vec<String> = Vec::new();
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--ignore-case" | "-I" => ignore_case = true,
+            "--time" => options.time = true,
+            "--json" => options.json = true,
+            "--clipboard" => options.clipboard = true,
+            "--to-clipboard" => options.to_clipboard = true,
+            "--show-changes" => options.show_changes = true,
+            "--all" => options.all = true,
+            "--explain" => options.explain = true,
+            "--output" => {
+                let path = iter.next().ok_or_else(|| {
+                    TransformError::InvalidArguments("--output requires a path".to_string())
+                })?;
+                options.output = Some(std::path::PathBuf::from(path));
+            }
+            "--watch" => {
+                let path = iter.next().ok_or_else(|| {
+                    TransformError::InvalidArguments("--watch requires a path".to_string())
+                })?;
+                options.watch = Some(std::path::PathBuf::from(path));
+            }
+            flag if flag.starts_with("--") => {
+                return Err(TransformError::InvalidArguments(format!(
+                    "unknown flag: {arg}"
+                )));
+            }
+            _ => positional.push(arg.clone()),
+        }
+    }
+
+    if let Some((name, rest)) = positional.split_first() {
+        options.command = Some(get_command(name)?);
+        options.sub = SubCommand::parse(rest)?;
+    }
+    options.sub.ignore_case = ignore_case;
+
+    if options.watch.is_some() && options.command.is_none() {
+        return Err(TransformError::InvalidArguments(
+            "--watch requires a command to run".to_string(),
+        ));
+    }
+
+    Ok(options)
+}
+
+/// Serializes parsed options back into the command line that reproduces
+/// them, for `--explain`. Flags come out in a fixed order and `key:value`
+/// tokens with whitespace are quoted the way [`split_args`] reads them;
+/// boolean flags left at their defaults produce nothing.
+pub fn explain(options: &CliOptions) -> String {
+    let mut parts = vec!["hw-07".to_string()];
+    if options.sub.ignore_case {
+        parts.push("--ignore-case".to_string());
+    }
+    if options.time {
+        parts.push("--time".to_string());
+    }
+    if options.json {
+        parts.push("--json".to_string());
+    }
+    if options.clipboard {
+        parts.push("--clipboard".to_string());
+    }
+    if options.to_clipboard {
+        parts.push("--to-clipboard".to_string());
+    }
+    if options.show_changes {
+        parts.push("--show-changes".to_string());
+    }
+    if options.all {
+        parts.push("--all".to_string());
+    }
+    if let Some(path) = &options.output {
+        parts.push("--output".to_string());
+        parts.push(path.display().to_string());
+    }
+    if let Some(path) = &options.watch {
+        parts.push("--watch".to_string());
+        parts.push(path.display().to_string());
+    }
+    if let Some(command) = options.command {
+        parts.push(command.as_ref().to_string());
+        for (key, value) in options.sub.entries() {
+            if value.chars().any(char::is_whitespace) {
+                parts.push(format!("\"{key}:{value}\""));
+            } else {
+                parts.push(format!("{key}:{value}"));
+            }
+        }
+    }
+    parts.join(" ")
+}
+
+pub fn get_command(name: &str) -> Result<Command, TransformError> {
+    Command::from_str(name)
+}
+
+/// Parses one interactive line: `<command> [key:value ...] [inline input]`.
+/// Tokens with a `key:` prefix right after the command become sub-command
+/// arguments; everything after the first plain token is inline input.
+pub fn parse_command(line: &str) -> Result<(Command, SubCommand, String), TransformError> {
+    let tokens = split_args(line);
+    let (name, rest) = tokens.split_first().ok_or_else(|| {
+        TransformError::InvalidArguments("empty command line".to_string())
+    })?;
+    let command = get_command(name)?;
+
+    let arg_count = rest
+        .iter()
+        .take_while(|t| {
+            t.split_once(':')
+                .is_some_and(|(k, _)| !k.is_empty() && k.chars().all(|c| c.is_ascii_lowercase() || c == '-'))
+        })
+        .count();
+    let sub = SubCommand::parse(&rest[..arg_count])?;
+    let inline = rest[arg_count..].join(" ");
+
+    Ok((command, sub, inline))
+}
+
+/// Splits a line into whitespace-separated tokens, keeping double-quoted
+/// spans together (quotes are stripped).
+pub fn split_args(line: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+
+    for c in line.chars() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+pub fn read_stdin() -> io::Result<String> {
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)?;
+    Ok(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_command_with_args_and_inline_input() {
+        let (command, sub, inline) = parse_command("wrap w:10 hello there world").unwrap();
+        assert_eq!(command, Command::Wrap);
+        assert_eq!(sub.get("w"), Some("10"));
+        assert_eq!(inline, "hello there world");
+    }
+
+    #[test]
+    fn split_args_keeps_quoted_spans() {
+        let tokens = split_args(r#"csv d:";" f:json"#);
+        assert_eq!(tokens, vec!["csv", "d:;", "f:json"]);
+    }
+
+    #[test]
+    fn ignore_case_flag_reaches_the_transform() {
+        let args = vec!["--ignore-case".to_string(), "sort-lines".to_string()];
+        let options = parse_args(&args).unwrap();
+        assert!(options.sub.ignore_case);
+
+        let out = crate::text_utils::transmute(
+            options.command.unwrap(),
+            &options.sub,
+            "banana\nApple".to_string(),
+        )
+        .unwrap();
+        assert_eq!(out, "Apple\nbanana");
+    }
+
+    #[test]
+    fn clipboard_flag_selects_the_source() {
+        assert_eq!(select_source(false), Source::Stdin);
+        assert_eq!(select_source(true), Source::Clipboard);
+
+        let args = vec!["--clipboard".to_string(), "uppercase".to_string()];
+        let options = parse_args(&args).unwrap();
+        assert!(options.clipboard);
+        assert!(!options.to_clipboard);
+    }
+
+    #[test]
+    fn explain_round_trips_the_parsed_invocation() {
+        let args: Vec<String> = ["--time", "--ignore-case", "wrap", "w:10"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let options = parse_args(&args).unwrap();
+
+        let line = explain(&options);
+        assert_eq!(line, "hw-07 --ignore-case --time wrap w:10");
+
+        let tokens = split_args(&line);
+        let reparsed = parse_args(&tokens[1..]).unwrap();
+        assert_eq!(reparsed.command, options.command);
+        assert!(reparsed.time);
+        assert!(reparsed.sub.ignore_case);
+        assert_eq!(reparsed.sub.get("w"), Some("10"));
+    }
+
+    #[test]
+    fn rejects_unknown_flags() {
+        let args = vec!["--bogus".to_string()];
+        assert!(parse_args(&args).is_err());
+    }
+}
@@ -0,0 +1,120 @@
+use serde::Serialize as _;
+use serde_json::Value;
+
+use crate::text_utils::{SubCommand, TransformError};
+
+/// Parses the input as JSON and re-emits it indented, `indent:<n>`
+/// spaces per level (default 2).
+pub fn pretty(sub: &SubCommand, input: &str) -> Result<String, TransformError> {
+    let value = parse(input)?;
+    let indent = " ".repeat(sub.get_parsed::<usize>("indent")?.unwrap_or(2));
+    let mut out = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+    let mut ser = serde_json::Serializer::with_formatter(&mut out, formatter);
+    value
+        .serialize(&mut ser)
+        .map_err(|e| TransformError::Json(e.to_string()))?;
+    Ok(String::from_utf8(out).expect("serde_json emits UTF-8"))
+}
+
+/// Parses the input as JSON and minifies it onto one line.
+pub fn compact(input: &str) -> Result<String, TransformError> {
+    let value = parse(input)?;
+    serde_json::to_string(&value).map_err(|e| TransformError::Json(e.to_string()))
+}
+
+/// Navigates the parsed JSON along `path:a.b.0.c` — object keys and
+/// numeric array indices separated by dots — and prints the value found
+/// there. Strings print bare; everything else prints as compact JSON.
+pub fn get(sub: &SubCommand, input: &str) -> Result<String, TransformError> {
+    let path = sub.get("path").ok_or_else(|| {
+        TransformError::InvalidArguments("json-get requires path:<a.b.0.c>".to_string())
+    })?;
+
+    let root = parse(input)?;
+    let mut current = &root;
+    for segment in path.split('.') {
+        current = match current {
+            Value::Object(map) => map.get(segment).ok_or_else(|| {
+                TransformError::Json(format!("no key '{segment}' along path '{path}'"))
+            })?,
+            Value::Array(items) => {
+                let index: usize = segment.parse().map_err(|_| {
+                    TransformError::Json(format!(
+                        "array needs a numeric index, got '{segment}' along path '{path}'"
+                    ))
+                })?;
+                items.get(index).ok_or_else(|| {
+                    TransformError::Json(format!(
+                        "index {index} out of bounds ({} elements) along path '{path}'",
+                        items.len()
+                    ))
+                })?
+            }
+            _ => {
+                return Err(TransformError::Json(format!(
+                    "cannot descend into a scalar at '{segment}' along path '{path}'"
+                )))
+            }
+        };
+    }
+
+    match current {
+        Value::String(s) => Ok(s.clone()),
+        other => serde_json::to_string(other).map_err(|e| TransformError::Json(e.to_string())),
+    }
+}
+
+/// serde_json's errors already carry the line and column of the problem,
+/// which is exactly what we want to surface.
+fn parse(input: &str) -> Result<Value, TransformError> {
+    serde_json::from_str(input).map_err(|e| TransformError::Json(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pretty_prints_with_configurable_indent() {
+        let out = pretty(&SubCommand::default(), r#"{"a":1,"b":[2,3]}"#).unwrap();
+        assert_eq!(out, "{\n  \"a\": 1,\n  \"b\": [\n    2,\n    3\n  ]\n}");
+
+        let sub = SubCommand::parse(&["indent:4".to_string()]).unwrap();
+        let out = pretty(&sub, r#"{"a":1}"#).unwrap();
+        assert_eq!(out, "{\n    \"a\": 1\n}");
+    }
+
+    #[test]
+    fn compact_minifies() {
+        let out = compact("{\n  \"a\": 1,\n  \"b\": [2, 3]\n}").unwrap();
+        assert_eq!(out, r#"{"a":1,"b":[2,3]}"#);
+    }
+
+    #[test]
+    fn get_extracts_nested_values() {
+        let doc = r#"{"user":{"name":"Ada","langs":["en","fr"]}}"#;
+
+        let sub = SubCommand::parse(&["path:user.name".to_string()]).unwrap();
+        assert_eq!(get(&sub, doc).unwrap(), "Ada");
+
+        let sub = SubCommand::parse(&["path:user.langs.1".to_string()]).unwrap();
+        assert_eq!(get(&sub, doc).unwrap(), "fr");
+
+        let sub = SubCommand::parse(&["path:user.langs".to_string()]).unwrap();
+        assert_eq!(get(&sub, doc).unwrap(), r#"["en","fr"]"#);
+    }
+
+    #[test]
+    fn get_reports_missing_paths() {
+        let sub = SubCommand::parse(&["path:user.email".to_string()]).unwrap();
+        let err = get(&sub, r#"{"user":{"name":"Ada"}}"#).unwrap_err();
+        assert!(err.to_string().contains("no key 'email'"), "got: {err}");
+    }
+
+    #[test]
+    fn invalid_json_reports_the_position() {
+        let err = compact(r#"{"a": }"#).unwrap_err();
+        assert!(err.to_string().contains("column 7"), "got: {err}");
+    }
+}
@@ -0,0 +1,31 @@
+use whatlang::detect;
+
+/// Reports the most likely language of the input with its confidence,
+/// e.g. `English (0.97)`. Inputs too short or ambiguous to classify
+/// report `unknown` instead of a wild guess.
+pub fn detect_lang(input: &str) -> String {
+    match detect(input) {
+        Some(info) if info.confidence() >= 0.1 => {
+            format!("{} ({:.2})", info.lang().eng_name(), info.confidence())
+        }
+        Some(_) | None => "unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_english() {
+        let out = detect_lang("The quick brown fox jumps over the lazy dog every morning.");
+        assert!(out.starts_with("English ("), "got: {out}");
+    }
+
+    #[test]
+    fn detects_non_english() {
+        let out = detect_lang("Der schnelle braune Fuchs springt über den faulen Hund hinweg.");
+        assert!(!out.starts_with("English"), "got: {out}");
+        assert_ne!(out, "unknown");
+    }
+}
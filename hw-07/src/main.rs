@@ -0,0 +1,162 @@
+use std::env;
+use std::io::{self, BufRead};
+use std::sync::mpsc;
+use std::thread;
+
+mod chart;
+mod code;
+mod csv_utils;
+mod diff;
+mod distance;
+mod extract;
+mod fix_encoding;
+mod fs_util;
+mod generate;
+mod grep;
+mod hash;
+mod image_info;
+mod input;
+mod json;
+mod lang;
+mod markdown;
+mod numwords;
+mod pipe;
+mod qr;
+mod redact;
+mod sink;
+mod text_utils;
+mod time;
+mod watch;
+
+use text_utils::{Command, Registry, SubCommand};
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let options = match input::parse_args(&args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    // `--explain` prints the reproducing invocation instead of running.
+    if options.explain {
+        println!("{}", input::explain(&options));
+        return;
+    }
+
+    let registry = Registry::new();
+    let result = match options.command {
+        Some(command) if options.watch.is_some() => watch::run_watch(&registry, command, &options),
+        Some(command) => run_oneshot(&registry, command, &options),
+        None => run_interactive(&registry, &options),
+    };
+
+    if let Err(e) = result {
+        eprintln!("Error: {e}");
+        std::process::exit(1);
+    }
+}
+
+/// Single-command mode: the input is the whole of stdin, or the
+/// clipboard with `--clipboard`.
+fn run_oneshot(
+    registry: &Registry,
+    command: Command,
+    options: &input::CliOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let text = match input::select_source(options.clipboard) {
+        input::Source::Stdin => input::read_stdin()?,
+        input::Source::Clipboard => input::read_clipboard()?,
+    };
+    execute_command(registry, command, &options.sub, text, options)?;
+    Ok(())
+}
+
+/// Interactive mode: one thread reads and parses lines, the other
+/// executes the transformations, connected by a channel.
+fn run_interactive(
+    registry: &Registry,
+    options: &input::CliOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    eprintln!("Enter <command> [key:value ...] <input> (Ctrl-D to quit):");
+
+    let (tx, rx) = mpsc::channel::<(Command, SubCommand, String)>();
+
+    let reader = thread::spawn(move || {
+        let stdin = io::stdin();
+        for line in stdin.lock().lines() {
+            let line = match line {
+                Ok(line) => line,
+                Err(e) => {
+                    eprintln!("Failed to read line: {e}");
+                    break;
+                }
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            match input::parse_command(&line) {
+                Ok(parsed) => {
+                    if tx.send(parsed).is_err() {
+                        break;
+                    }
+                }
+                Err(e) => eprintln!("Error: {e}"),
+            }
+        }
+    });
+
+    for (command, sub, text) in rx {
+        if let Err(e) = execute_command(registry, command, &sub, text, options) {
+            eprintln!("Error: {e}");
+        }
+    }
+
+    reader.join().expect("reader thread panicked");
+    Ok(())
+}
+
+/// Runs one transformation through the registry and fans the result out
+/// to every configured sink (stdout, `--output` file, clipboard). A
+/// failing sink is reported but never stops the others. With `--time`,
+/// the elapsed duration goes to stderr so it never mixes into the result.
+fn execute_command(
+    registry: &Registry,
+    command: Command,
+    sub: &SubCommand,
+    text: String,
+    options: &input::CliOptions,
+) -> Result<(), text_utils::TransformError> {
+    // `--show-changes` needs the untransformed input to diff against.
+    let original = options.show_changes.then(|| text.clone());
+    let (result, elapsed) = text_utils::timed(|| registry.transmute(command.as_ref(), sub, text));
+    if let (Some(original), Ok(output)) = (&original, &result) {
+        eprintln!("{}", diff::render_changes(original, output, options.all));
+    }
+    let rendered = if options.json {
+        // Errors are part of the envelope, so they are not propagated.
+        text_utils::json_envelope(command.as_ref(), &result)
+    } else {
+        result?
+    };
+
+    let mut sinks = sink::build_sinks(options)?;
+    let failures = sink::write_all(&mut sinks, &rendered);
+    if options.time {
+        eprintln!("{command} took {elapsed:?}");
+    }
+    if !failures.is_empty() {
+        let summary: Vec<String> = failures
+            .iter()
+            .map(|(name, e)| format!("{name}: {e}"))
+            .collect();
+        return Err(text_utils::TransformError::Other(format!(
+            "sink errors: {}",
+            summary.join("; ")
+        )));
+    }
+    Ok(())
+}
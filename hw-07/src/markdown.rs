@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+use std::fmt::Write as _;
+
+use pulldown_cmark::{Event, Parser, Tag};
+
+use crate::text_utils::{SubCommand, TransformError};
+
+/// Wraps the input in a triple-backtick fence tagged with `lang:<name>`
+/// (untagged without it). `highlight:true` instead renders a terminal
+/// preview with ANSI colors via syntect; the plain fence path never
+/// touches the syntax definitions, so only previews pay for loading them.
+pub fn codeblock(sub: &SubCommand, input: &str) -> Result<String, TransformError> {
+    let lang = sub.get("lang").unwrap_or("");
+    let body = input.trim_end_matches('\n');
+    if !sub.get_bool("highlight") {
+        return Ok(format!("```{lang}\n{body}\n```"));
+    }
+
+    let syntaxes = syntect::parsing::SyntaxSet::load_defaults_newlines();
+    let syntax = match lang {
+        "" => syntaxes.find_syntax_plain_text(),
+        token => syntaxes.find_syntax_by_token(token).ok_or_else(|| {
+            TransformError::InvalidArguments(format!("no syntax definition for '{token}'"))
+        })?,
+    };
+    let themes = syntect::highlighting::ThemeSet::load_defaults();
+    let mut highlighter =
+        syntect::easy::HighlightLines::new(syntax, &themes.themes["base16-ocean.dark"]);
+
+    let mut out = String::new();
+    for line in syntect::util::LinesWithEndings::from(body) {
+        let regions = highlighter
+            .highlight_line(line, &syntaxes)
+            .map_err(|e| TransformError::Other(format!("highlighting failed: {e}")))?;
+        out.push_str(&syntect::util::as_24_bit_terminal_escaped(&regions, false));
+    }
+    out.push_str("\x1b[0m");
+    Ok(out)
+}
+
+/// Builds a nested, linked table of contents from the `#`-level headers
+/// of a Markdown document. Anchors follow GitHub's scheme: slugified
+/// header text, with `-1`, `-2`, ... suffixes for duplicates.
+pub fn toc(input: &str) -> String {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    let mut out = String::new();
+
+    let mut current: Option<(u32, String)> = None;
+    for event in Parser::new(input) {
+        match event {
+            Event::Start(Tag::Heading(level, _, _)) => {
+                current = Some((level as u32, String::new()));
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some((_, buffer)) = current.as_mut() {
+                    buffer.push_str(&text);
+                }
+            }
+            Event::End(Tag::Heading(..)) => {
+                if let Some((level, text)) = current.take() {
+                    let slug = slug::slugify(&text);
+                    let count = seen.entry(slug.clone()).or_insert(0);
+                    let anchor = if *count == 0 {
+                        slug.clone()
+                    } else {
+                        format!("{slug}-{count}")
+                    };
+                    *count += 1;
+
+                    let indent = "  ".repeat(level.saturating_sub(1) as usize);
+                    let _ = writeln!(out, "{indent}- [{text}](#{anchor})");
+                }
+            }
+            _ => {}
+        }
+    }
+
+    out.pop();
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_nested_linked_toc() {
+        let doc = "# Intro\n\nsome text\n\n## Getting Started\n\n## Usage Notes\n";
+        let out = toc(doc);
+        assert_eq!(
+            out,
+            "- [Intro](#intro)\n  - [Getting Started](#getting-started)\n  - [Usage Notes](#usage-notes)"
+        );
+    }
+
+    #[test]
+    fn codeblock_wraps_input_in_a_tagged_fence() {
+        let sub = SubCommand::parse(&["lang:rust".to_string()]).unwrap();
+        let out = codeblock(&sub, "fn main() {}\n").unwrap();
+        assert_eq!(out, "```rust\nfn main() {}\n```");
+
+        let out = codeblock(&SubCommand::default(), "plain").unwrap();
+        assert_eq!(out, "```\nplain\n```");
+    }
+
+    #[test]
+    fn highlight_emits_ansi_colors_and_rejects_unknown_languages() {
+        let sub = SubCommand::parse(&["lang:rust".to_string(), "highlight:true".to_string()])
+            .unwrap();
+        let out = codeblock(&sub, "fn main() {}").unwrap();
+        assert!(out.contains("\x1b[38;2;"), "got:\n{out}");
+        assert!(out.ends_with("\x1b[0m"));
+
+        let sub = SubCommand::parse(&["lang:klingon".to_string(), "highlight:true".to_string()])
+            .unwrap();
+        assert!(codeblock(&sub, "nuqneH").is_err());
+    }
+
+    #[test]
+    fn duplicate_headers_get_numbered_anchors() {
+        let doc = "## Setup\n\n## Setup\n\n## Setup\n";
+        let out = toc(doc);
+        assert_eq!(
+            out,
+            "  - [Setup](#setup)\n  - [Setup](#setup-1)\n  - [Setup](#setup-2)"
+        );
+    }
+}
@@ -0,0 +1,144 @@
+/// Converts integer tokens in the input to their English word form
+/// (42 → "forty-two"), leaving everything else untouched. Runs of
+/// whitespace collapse to single spaces.
+pub fn numwords(input: &str) -> String {
+    input
+        .split_whitespace()
+        .map(|token| match token.parse::<i64>() {
+            Ok(n) => number_to_words(n),
+            Err(_) => token.to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+const ONES: [&str; 20] = [
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+
+const TENS: [&str; 10] = [
+    "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+
+const SCALES: [&str; 7] = [
+    "",
+    "thousand",
+    "million",
+    "billion",
+    "trillion",
+    "quadrillion",
+    "quintillion",
+];
+
+pub fn number_to_words(n: i64) -> String {
+    if n < 0 {
+        // i64::MIN has no positive counterpart; go through u64.
+        return format!("minus {}", unsigned_to_words(n.unsigned_abs()));
+    }
+    unsigned_to_words(n as u64)
+}
+
+fn unsigned_to_words(n: u64) -> String {
+    if n < 20 {
+        return ONES[n as usize].to_string();
+    }
+
+    // Split into groups of three digits, least significant first.
+    let mut groups: Vec<u64> = Vec::new();
+    let mut rest = n;
+    while rest > 0 {
+        groups.push(rest % 1000);
+        rest /= 1000;
+    }
+
+    let mut parts: Vec<String> = Vec::new();
+    for (i, &group) in groups.iter().enumerate().rev() {
+        if group == 0 {
+            continue;
+        }
+        let mut part = under_thousand(group);
+        if !SCALES[i].is_empty() {
+            part.push(' ');
+            part.push_str(SCALES[i]);
+        }
+        parts.push(part);
+    }
+    parts.join(" ")
+}
+
+fn under_thousand(n: u64) -> String {
+    debug_assert!(n < 1000);
+    let mut out = String::new();
+    if n >= 100 {
+        out.push_str(ONES[(n / 100) as usize]);
+        out.push_str(" hundred");
+    }
+    let rem = n % 100;
+    if rem > 0 {
+        if !out.is_empty() {
+            out.push(' ');
+        }
+        out.push_str(&under_hundred(rem));
+    }
+    out
+}
+
+fn under_hundred(n: u64) -> String {
+    debug_assert!(n < 100);
+    if n < 20 {
+        return ONES[n as usize].to_string();
+    }
+    let mut out = TENS[(n / 10) as usize].to_string();
+    if !n.is_multiple_of(10) {
+        out.push('-');
+        out.push_str(ONES[(n % 10) as usize]);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_zero_and_small_numbers() {
+        assert_eq!(number_to_words(0), "zero");
+        assert_eq!(number_to_words(42), "forty-two");
+        assert_eq!(number_to_words(-7), "minus seven");
+    }
+
+    #[test]
+    fn converts_large_numbers() {
+        assert_eq!(
+            number_to_words(1_234_567),
+            "one million two hundred thirty-four thousand five hundred sixty-seven"
+        );
+    }
+
+    #[test]
+    fn only_integer_tokens_change() {
+        assert_eq!(
+            numwords("I have 2 cats and 0 dogs, v1.2 stays"),
+            "I have two cats and zero dogs, v1.2 stays"
+        );
+    }
+}
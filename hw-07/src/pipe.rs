@@ -0,0 +1,65 @@
+use std::io::Write as _;
+use std::process::{Command as Process, Stdio};
+
+use crate::text_utils::{SubCommand, TransformError};
+
+/// Pipes the input through the external command given by
+/// `cmd:"sort -u"`: stdin goes to its stdin, its stdout comes back.
+///
+/// The value splits on whitespace and runs directly — no shell is ever
+/// invoked, so pipes, globs, and `$(...)` have no effect and cannot be
+/// injected. A non-zero exit becomes an error carrying the command's
+/// stderr.
+pub fn pipe(sub: &SubCommand, input: &str) -> Result<String, TransformError> {
+    let cmd = sub.get("cmd").ok_or_else(|| {
+        TransformError::InvalidArguments("pipe requires cmd:\"<program> [args]\"".to_string())
+    })?;
+    let mut parts = cmd.split_whitespace();
+    let program = parts
+        .next()
+        .ok_or_else(|| TransformError::InvalidArguments("pipe cmd is empty".to_string()))?;
+
+    let mut child = Process::new(program)
+        .args(parts)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| TransformError::Other(format!("failed to spawn {program}: {e}")))?;
+
+    // Dropping the handle after writing closes the pipe, letting the
+    // child see EOF.
+    child
+        .stdin
+        .take()
+        .expect("stdin was piped")
+        .write_all(input.as_bytes())?;
+    let output = child.wait_with_output()?;
+
+    if !output.status.success() {
+        return Err(TransformError::Other(format!(
+            "{program} failed ({}): {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pipes_input_through_cat() {
+        let sub = SubCommand::parse(&["cmd:cat".to_string()]).unwrap();
+        let out = pipe(&sub, "line one\nline two\n").unwrap();
+        assert_eq!(out, "line one\nline two\n");
+    }
+
+    #[test]
+    fn non_zero_exit_is_an_error() {
+        let sub = SubCommand::parse(&["cmd:false".to_string()]).unwrap();
+        assert!(pipe(&sub, "").is_err());
+    }
+}
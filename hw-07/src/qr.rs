@@ -0,0 +1,53 @@
+use qrcode::render::unicode;
+use qrcode::QrCode;
+
+use crate::text_utils::{SubCommand, TransformError};
+
+/// Encodes the input as a QR code and renders it with Unicode half-block
+/// characters, two modules per terminal row. With `out:<path.png>` the
+/// code is rasterized and saved as a PNG instead.
+///
+/// QR capacity tops out just under 3 KB of bytes; longer input errors
+/// rather than silently truncating.
+pub fn qr(sub: &SubCommand, input: &str) -> Result<String, TransformError> {
+    let code = encode(input.trim_end())?;
+    match sub.get("out") {
+        Some(path) => {
+            let image = code.render::<image::Luma<u8>>().build();
+            image
+                .save(path)
+                .map_err(|e| TransformError::Other(format!("failed to save PNG: {e}")))?;
+            Ok(format!("saved {path}"))
+        }
+        None => Ok(code.render::<unicode::Dense1x2>().build()),
+    }
+}
+
+fn encode(data: &str) -> Result<QrCode, TransformError> {
+    QrCode::new(data.as_bytes())
+        .map_err(|e| TransformError::InvalidArguments(format!("cannot encode as QR: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_string_yields_a_module_matrix() {
+        let code = encode("https://example.com").unwrap();
+        assert!(code.width() > 0);
+
+        let rendered = qr(&SubCommand::default(), "https://example.com").unwrap();
+        assert!(!rendered.is_empty());
+        assert!(rendered.lines().count() > 1);
+    }
+
+    #[test]
+    fn input_beyond_qr_capacity_errors() {
+        let huge = "x".repeat(4000);
+        assert!(matches!(
+            encode(&huge),
+            Err(TransformError::InvalidArguments(_))
+        ));
+    }
+}
@@ -0,0 +1,31 @@
+use crate::extract::{EMAIL_RE, URL_RE};
+use crate::text_utils::{SubCommand, TransformError};
+
+const PLACEHOLDER: &str = "[REDACTED]";
+
+/// Replaces emails and/or URLs in the input with a placeholder.
+/// Selected with `kind:email`, `kind:url`, or `kind:all` (the default).
+pub fn redact(sub: &SubCommand, input: &str) -> Result<String, TransformError> {
+    match sub.get("kind").unwrap_or("all") {
+        "email" => Ok(EMAIL_RE.replace_all(input, PLACEHOLDER).into_owned()),
+        "url" => Ok(URL_RE.replace_all(input, PLACEHOLDER).into_owned()),
+        "all" => {
+            let pass = EMAIL_RE.replace_all(input, PLACEHOLDER);
+            Ok(URL_RE.replace_all(&pass, PLACEHOLDER).into_owned())
+        }
+        other => Err(TransformError::InvalidArguments(format!(
+            "unknown kind '{other}', expected email, url, or all"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_both_by_default() {
+        let out = redact(&SubCommand::default(), "a@b.com at https://x.io").unwrap();
+        assert_eq!(out, "[REDACTED] at [REDACTED]");
+    }
+}
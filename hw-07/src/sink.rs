@@ -0,0 +1,157 @@
+use std::io::{self, Write};
+
+use crate::input::CliOptions;
+use crate::text_utils::TransformError;
+
+/// One destination for a transform result. Sinks are independent:
+/// writing to all of them continues past individual failures.
+pub trait Sink {
+    fn name(&self) -> &'static str;
+    fn put(&mut self, text: &str) -> Result<(), TransformError>;
+}
+
+/// Sink over anything `Write`: stdout, a file, or a test buffer.
+pub struct WriteSink<W: Write> {
+    pub name: &'static str,
+    pub writer: W,
+}
+
+impl<W: Write> Sink for WriteSink<W> {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn put(&mut self, text: &str) -> Result<(), TransformError> {
+        writeln!(self.writer, "{text}")?;
+        Ok(())
+    }
+}
+
+/// Writes the result to a file atomically, so an interrupted run never
+/// leaves a truncated output file behind.
+pub struct FileSink {
+    pub path: std::path::PathBuf,
+}
+
+impl Sink for FileSink {
+    fn name(&self) -> &'static str {
+        "file"
+    }
+
+    fn put(&mut self, text: &str) -> Result<(), TransformError> {
+        crate::fs_util::write_atomic(&self.path, format!("{text}\n").as_bytes())?;
+        Ok(())
+    }
+}
+
+/// Copies the result to the system clipboard.
+pub struct ClipboardSink;
+
+impl Sink for ClipboardSink {
+    fn name(&self) -> &'static str {
+        "clipboard"
+    }
+
+    fn put(&mut self, text: &str) -> Result<(), TransformError> {
+        crate::input::write_clipboard(text)
+    }
+}
+
+/// The sinks selected by the command-line flags. Stdout is always on;
+/// `--output <path>` and `--to-clipboard` add more.
+pub fn build_sinks(options: &CliOptions) -> Result<Vec<Box<dyn Sink>>, TransformError> {
+    let mut sinks: Vec<Box<dyn Sink>> = vec![Box::new(WriteSink {
+        name: "stdout",
+        writer: io::stdout(),
+    })];
+    if let Some(path) = &options.output {
+        sinks.push(Box::new(FileSink { path: path.clone() }));
+    }
+    if options.to_clipboard {
+        sinks.push(Box::new(ClipboardSink));
+    }
+    Ok(sinks)
+}
+
+/// Writes `text` to every sink and returns the failures; one bad sink
+/// never stops the others.
+pub fn write_all(sinks: &mut [Box<dyn Sink>], text: &str) -> Vec<(&'static str, TransformError)> {
+    sinks
+        .iter_mut()
+        .filter_map(|sink| sink.put(text).err().map(|e| (sink.name(), e)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// `Write` into a shared buffer the test can read back after the
+    /// sink has been boxed away.
+    #[derive(Clone, Default)]
+    struct SharedBuf(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    struct FailSink;
+
+    impl Sink for FailSink {
+        fn name(&self) -> &'static str {
+            "broken"
+        }
+
+        fn put(&mut self, _text: &str) -> Result<(), TransformError> {
+            Err(TransformError::Other("sink is broken".to_string()))
+        }
+    }
+
+    #[test]
+    fn every_sink_receives_the_same_output() {
+        let first = SharedBuf::default();
+        let second = SharedBuf::default();
+        let mut sinks: Vec<Box<dyn Sink>> = vec![
+            Box::new(WriteSink {
+                name: "first",
+                writer: first.clone(),
+            }),
+            Box::new(WriteSink {
+                name: "second",
+                writer: second.clone(),
+            }),
+        ];
+
+        let errors = write_all(&mut sinks, "result");
+        assert!(errors.is_empty());
+        assert_eq!(*first.0.borrow(), b"result\n");
+        assert_eq!(*second.0.borrow(), b"result\n");
+    }
+
+    #[test]
+    fn a_failing_sink_does_not_stop_the_others() {
+        let good = SharedBuf::default();
+        let mut sinks: Vec<Box<dyn Sink>> = vec![
+            Box::new(FailSink),
+            Box::new(WriteSink {
+                name: "good",
+                writer: good.clone(),
+            }),
+        ];
+
+        let errors = write_all(&mut sinks, "result");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, "broken");
+        assert_eq!(*good.0.borrow(), b"result\n");
+    }
+}
@@ -0,0 +1,2065 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write as _;
+use std::str::FromStr;
+
+use base64::Engine as _;
+use thiserror::Error;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::chart;
+use crate::code;
+use crate::csv_utils;
+use crate::diff;
+use crate::distance;
+use crate::extract;
+use crate::fix_encoding;
+use crate::generate;
+use crate::grep;
+use crate::hash;
+use crate::image_info;
+use crate::json;
+use crate::lang;
+use crate::markdown;
+use crate::numwords;
+use crate::pipe;
+use crate::qr;
+use crate::redact;
+use crate::time;
+
+#[derive(Debug, Error)]
+pub enum TransformError {
+    #[error("Invalid command: {0}")]
+    InvalidCommand(String),
+    #[error("Invalid arguments: {0}")]
+    InvalidArguments(String),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("CSV error: {0}")]
+    Csv(String),
+    #[error("JSON error: {0}")]
+    Json(String),
+    #[error("{0}")]
+    Other(String),
+}
+
+/// All transformations the tool knows how to perform.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Lowercase,
+    Uppercase,
+    NoSpaces,
+    Slugify,
+    Ascii,
+    Reverse,
+    ReverseWords,
+    Wrap,
+    Fold,
+    NumWords,
+    WordCount,
+    Measure,
+    ReadTime,
+    CharFreq,
+    WordFreq,
+    SortLines,
+    Expand,
+    Unexpand,
+    Base64Encode,
+    Base64Decode,
+    Base32Encode,
+    Base32Decode,
+    Rot13,
+    Cstr,
+    Quote,
+    Unquote,
+    Affix,
+    Align,
+    FilterChars,
+    Mask,
+    Demojibake,
+    Histogram,
+    HtmlEscape,
+    HtmlUnescape,
+    Banner,
+    CommentBox,
+    Lorem,
+    JsonPretty,
+    JsonCompact,
+    JsonGet,
+    Toc,
+    DetectLang,
+    ImageInfo,
+    Csv,
+    CsvCheck,
+    Extract,
+    GrepCount,
+    Redact,
+    Diff,
+    WordDiff,
+    Distance,
+    Hash,
+    Pipe,
+    Qr,
+    Timestamp,
+    ProseStats,
+    Ruler,
+    Codeblock,
+    NormalizeList,
+    Tokens,
+    When,
+    Rtrim,
+    EnsureFinalNewline,
+    Fit,
+    CsvDialect,
+    Verify,
+    StripComments,
+    GenId,
+    Reindent,
+    Head,
+    Tail,
+    DumbQuotes,
+    SmartQuotes,
+}
+
+impl Command {
+    /// Every built-in command, for listings and typo suggestions.
+    pub const ALL: [Command; 73] = [
+        Command::Lowercase,
+        Command::Uppercase,
+        Command::NoSpaces,
+        Command::Slugify,
+        Command::Ascii,
+        Command::Reverse,
+        Command::ReverseWords,
+        Command::Wrap,
+        Command::Fold,
+        Command::NumWords,
+        Command::WordCount,
+        Command::Measure,
+        Command::ReadTime,
+        Command::CharFreq,
+        Command::WordFreq,
+        Command::SortLines,
+        Command::Expand,
+        Command::Unexpand,
+        Command::Base64Encode,
+        Command::Base64Decode,
+        Command::Base32Encode,
+        Command::Base32Decode,
+        Command::Rot13,
+        Command::Cstr,
+        Command::Quote,
+        Command::Unquote,
+        Command::Affix,
+        Command::Align,
+        Command::FilterChars,
+        Command::Mask,
+        Command::Demojibake,
+        Command::Histogram,
+        Command::HtmlEscape,
+        Command::HtmlUnescape,
+        Command::Banner,
+        Command::CommentBox,
+        Command::Lorem,
+        Command::JsonPretty,
+        Command::JsonCompact,
+        Command::JsonGet,
+        Command::Toc,
+        Command::DetectLang,
+        Command::ImageInfo,
+        Command::Csv,
+        Command::CsvCheck,
+        Command::Extract,
+        Command::GrepCount,
+        Command::Redact,
+        Command::Diff,
+        Command::WordDiff,
+        Command::Distance,
+        Command::Hash,
+        Command::Pipe,
+        Command::Qr,
+        Command::Timestamp,
+        Command::ProseStats,
+        Command::Ruler,
+        Command::Codeblock,
+        Command::NormalizeList,
+        Command::Tokens,
+        Command::When,
+        Command::Rtrim,
+        Command::EnsureFinalNewline,
+        Command::Fit,
+        Command::CsvDialect,
+        Command::Verify,
+        Command::StripComments,
+        Command::GenId,
+        Command::Reindent,
+        Command::Head,
+        Command::Tail,
+        Command::DumbQuotes,
+        Command::SmartQuotes,
+    ];
+}
+
+/// The known command name closest to `input`, if it is close enough
+/// (edit distance ≤ 2) to look like a typo rather than a different word.
+fn closest_command(input: &str) -> Option<&'static str> {
+    Command::ALL
+        .iter()
+        .map(|command| command.as_ref())
+        .map(|name| (strsim::levenshtein(input, name), name))
+        .min()
+        .filter(|(distance, _)| *distance <= 2)
+        .map(|(_, name)| name)
+}
+
+impl FromStr for Command {
+    type Err = TransformError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "lowercase" => Ok(Command::Lowercase),
+            "uppercase" => Ok(Command::Uppercase),
+            "no-spaces" => Ok(Command::NoSpaces),
+            "slugify" => Ok(Command::Slugify),
+            "ascii" => Ok(Command::Ascii),
+            "reverse" => Ok(Command::Reverse),
+            "reverse-words" => Ok(Command::ReverseWords),
+            "wrap" => Ok(Command::Wrap),
+            "fold" => Ok(Command::Fold),
+            "numwords" => Ok(Command::NumWords),
+            "wordcount" => Ok(Command::WordCount),
+            "measure" => Ok(Command::Measure),
+            "readtime" => Ok(Command::ReadTime),
+            "charfreq" => Ok(Command::CharFreq),
+            "word-freq" => Ok(Command::WordFreq),
+            "sort-lines" => Ok(Command::SortLines),
+            "expand" => Ok(Command::Expand),
+            "unexpand" => Ok(Command::Unexpand),
+            "base64-encode" => Ok(Command::Base64Encode),
+            "base64-decode" => Ok(Command::Base64Decode),
+            "base32-encode" => Ok(Command::Base32Encode),
+            "base32-decode" => Ok(Command::Base32Decode),
+            "rot13" => Ok(Command::Rot13),
+            "cstr" => Ok(Command::Cstr),
+            "quote" => Ok(Command::Quote),
+            "unquote" => Ok(Command::Unquote),
+            "affix" => Ok(Command::Affix),
+            "align" => Ok(Command::Align),
+            "filter-chars" => Ok(Command::FilterChars),
+            "mask" => Ok(Command::Mask),
+            "demojibake" => Ok(Command::Demojibake),
+            "histogram" => Ok(Command::Histogram),
+            "html-escape" => Ok(Command::HtmlEscape),
+            "html-unescape" => Ok(Command::HtmlUnescape),
+            "banner" => Ok(Command::Banner),
+            "comment-box" => Ok(Command::CommentBox),
+            "lorem" => Ok(Command::Lorem),
+            "json-pretty" => Ok(Command::JsonPretty),
+            "json-compact" => Ok(Command::JsonCompact),
+            "json-get" => Ok(Command::JsonGet),
+            "toc" => Ok(Command::Toc),
+            "detect-lang" => Ok(Command::DetectLang),
+            "imageinfo" => Ok(Command::ImageInfo),
+            "csv" => Ok(Command::Csv),
+            "csv-check" => Ok(Command::CsvCheck),
+            "extract" => Ok(Command::Extract),
+            "grep-count" => Ok(Command::GrepCount),
+            "redact" => Ok(Command::Redact),
+            "diff" => Ok(Command::Diff),
+            "word-diff" => Ok(Command::WordDiff),
+            "distance" => Ok(Command::Distance),
+            "hash" => Ok(Command::Hash),
+            "pipe" => Ok(Command::Pipe),
+            "qr" => Ok(Command::Qr),
+            "timestamp" => Ok(Command::Timestamp),
+            "prose-stats" => Ok(Command::ProseStats),
+            "ruler" => Ok(Command::Ruler),
+            "codeblock" => Ok(Command::Codeblock),
+            "normalize-list" => Ok(Command::NormalizeList),
+            "tokens" => Ok(Command::Tokens),
+            "when" => Ok(Command::When),
+            "rtrim" => Ok(Command::Rtrim),
+            "ensure-final-newline" => Ok(Command::EnsureFinalNewline),
+            "fit" => Ok(Command::Fit),
+            "csv-dialect" => Ok(Command::CsvDialect),
+            "verify" => Ok(Command::Verify),
+            "strip-comments" => Ok(Command::StripComments),
+            "gen-id" => Ok(Command::GenId),
+            "reindent" => Ok(Command::Reindent),
+            "head" => Ok(Command::Head),
+            "tail" => Ok(Command::Tail),
+            "dumbquotes" => Ok(Command::DumbQuotes),
+            "smartquotes" => Ok(Command::SmartQuotes),
+            other => {
+                let mut message = other.to_string();
+                if let Some(suggestion) = closest_command(other) {
+                    let _ = write!(message, ". Did you mean '{suggestion}'?");
+                }
+                Err(TransformError::InvalidCommand(message))
+            }
+        }
+    }
+}
+
+impl TryFrom<&str> for Command {
+    type Error = TransformError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl AsRef<str> for Command {
+    /// The canonical name, as accepted by `FromStr`.
+    fn as_ref(&self) -> &str {
+        match self {
+            Command::Lowercase => "lowercase",
+            Command::Uppercase => "uppercase",
+            Command::NoSpaces => "no-spaces",
+            Command::Slugify => "slugify",
+            Command::Ascii => "ascii",
+            Command::Reverse => "reverse",
+            Command::ReverseWords => "reverse-words",
+            Command::Wrap => "wrap",
+            Command::Fold => "fold",
+            Command::NumWords => "numwords",
+            Command::WordCount => "wordcount",
+            Command::Measure => "measure",
+            Command::ReadTime => "readtime",
+            Command::CharFreq => "charfreq",
+            Command::WordFreq => "word-freq",
+            Command::SortLines => "sort-lines",
+            Command::Expand => "expand",
+            Command::Unexpand => "unexpand",
+            Command::Base64Encode => "base64-encode",
+            Command::Base64Decode => "base64-decode",
+            Command::Base32Encode => "base32-encode",
+            Command::Base32Decode => "base32-decode",
+            Command::Rot13 => "rot13",
+            Command::Cstr => "cstr",
+            Command::Quote => "quote",
+            Command::Unquote => "unquote",
+            Command::Affix => "affix",
+            Command::Align => "align",
+            Command::FilterChars => "filter-chars",
+            Command::Mask => "mask",
+            Command::Demojibake => "demojibake",
+            Command::Histogram => "histogram",
+            Command::HtmlEscape => "html-escape",
+            Command::HtmlUnescape => "html-unescape",
+            Command::Banner => "banner",
+            Command::CommentBox => "comment-box",
+            Command::Lorem => "lorem",
+            Command::JsonPretty => "json-pretty",
+            Command::JsonCompact => "json-compact",
+            Command::JsonGet => "json-get",
+            Command::Toc => "toc",
+            Command::DetectLang => "detect-lang",
+            Command::ImageInfo => "imageinfo",
+            Command::Csv => "csv",
+            Command::CsvCheck => "csv-check",
+            Command::Extract => "extract",
+            Command::GrepCount => "grep-count",
+            Command::Redact => "redact",
+            Command::Diff => "diff",
+            Command::WordDiff => "word-diff",
+            Command::Distance => "distance",
+            Command::Hash => "hash",
+            Command::Pipe => "pipe",
+            Command::Qr => "qr",
+            Command::Timestamp => "timestamp",
+            Command::ProseStats => "prose-stats",
+            Command::Ruler => "ruler",
+            Command::Codeblock => "codeblock",
+            Command::NormalizeList => "normalize-list",
+            Command::Tokens => "tokens",
+            Command::When => "when",
+            Command::Rtrim => "rtrim",
+            Command::EnsureFinalNewline => "ensure-final-newline",
+            Command::Fit => "fit",
+            Command::CsvDialect => "csv-dialect",
+            Command::Verify => "verify",
+            Command::StripComments => "strip-comments",
+            Command::GenId => "gen-id",
+            Command::Reindent => "reindent",
+            Command::Head => "head",
+            Command::Tail => "tail",
+            Command::DumbQuotes => "dumbquotes",
+            Command::SmartQuotes => "smartquotes",
+        }
+    }
+}
+
+impl std::fmt::Display for Command {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_ref())
+    }
+}
+
+/// Extra `key:value` arguments given after the command name,
+/// e.g. `wrap w:40` or `csv d:";" f:json`.
+#[derive(Debug, Clone, Default)]
+pub struct SubCommand {
+    pairs: Vec<(String, String)>,
+    /// Global `--ignore-case`/`-I` flag; transforms that match or compare
+    /// text honor it, all others ignore it.
+    pub ignore_case: bool,
+}
+
+impl SubCommand {
+    pub fn parse(tokens: &[String]) -> Result<Self, TransformError> {
+        let mut pairs = Vec::new();
+        for token in tokens {
+            let (key, value) = token.split_once(':').ok_or_else(|| {
+                TransformError::InvalidArguments(format!(
+                    "expected key:value argument, got '{token}'"
+                ))
+            })?;
+            pairs.push((key.to_string(), value.to_string()));
+        }
+        Ok(SubCommand {
+            pairs,
+            ..SubCommand::default()
+        })
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.pairs
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn get_parsed<T: FromStr>(&self, key: &str) -> Result<Option<T>, TransformError> {
+        match self.get(key) {
+            None => Ok(None),
+            Some(v) => v.parse::<T>().map(Some).map_err(|_| {
+                TransformError::InvalidArguments(format!("could not parse '{v}' for '{key}'"))
+            }),
+        }
+    }
+
+    pub fn get_bool(&self, key: &str) -> bool {
+        matches!(self.get(key), Some("true") | Some("1") | Some("yes"))
+    }
+
+    /// The `key:value` pairs in the order they were given, for code that
+    /// serializes an invocation back to argument form.
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.pairs.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+}
+
+/// A named text transformation. The built-in [`Command`]s implement
+/// this, and library users can [`Registry::register`] their own without
+/// touching the enum. `sub` carries the `key:value` arguments;
+/// transforms without options can ignore it.
+pub trait Transform {
+    // The CLI itself only registers built-ins, so this is dead code from
+    // the binary's point of view; it exists for downstream registration.
+    #[allow(dead_code)]
+    fn name(&self) -> &str;
+    fn apply(&self, sub: &SubCommand, input: String) -> Result<String, TransformError>;
+}
+
+impl Transform for Command {
+    fn name(&self) -> &str {
+        self.as_ref()
+    }
+
+    fn apply(&self, sub: &SubCommand, input: String) -> Result<String, TransformError> {
+        transmute(*self, sub, input)
+    }
+}
+
+/// Looks transforms up by name: registered custom transforms first, the
+/// built-in commands as a fallback, so a custom transform may shadow a
+/// built-in of the same name.
+#[derive(Default)]
+pub struct Registry {
+    custom: HashMap<String, Box<dyn Transform>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Registry::default()
+    }
+
+    #[allow(dead_code)]
+    pub fn register(&mut self, transform: Box<dyn Transform>) {
+        self.custom.insert(transform.name().to_string(), transform);
+    }
+
+    /// Applies the transform registered (or built in) under `name`.
+    pub fn transmute(
+        &self,
+        name: &str,
+        sub: &SubCommand,
+        input: String,
+    ) -> Result<String, TransformError> {
+        match self.custom.get(name) {
+            Some(transform) => transform.apply(sub, input),
+            None => name.parse::<Command>()?.apply(sub, input),
+        }
+    }
+}
+
+/// Shapes one transform result as the `--json` envelope:
+/// `{"command":...,"ok":true,"output":...}` on success,
+/// `{"command":...,"ok":false,"error":...}` on failure.
+pub fn json_envelope(command: &str, result: &Result<String, TransformError>) -> String {
+    let value = match result {
+        Ok(output) => serde_json::json!({"command": command, "ok": true, "output": output}),
+        Err(e) => serde_json::json!({"command": command, "ok": false, "error": e.to_string()}),
+    };
+    value.to_string()
+}
+
+/// Runs `f` and returns its result unchanged, together with how long it
+/// took. Backs the `--time` flag.
+pub fn timed<T>(f: impl FnOnce() -> T) -> (T, std::time::Duration) {
+    let start = std::time::Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+/// Applies the given command to the input and returns the transformed text.
+pub fn transmute(
+    command: Command,
+    sub: &SubCommand,
+    input: String,
+) -> Result<String, TransformError> {
+    match command {
+        Command::Lowercase => Ok(input.to_lowercase()),
+        Command::Uppercase => Ok(input.to_uppercase()),
+        Command::NoSpaces => Ok(input.replace(' ', "")),
+        Command::Slugify => slugify(sub, &input),
+        Command::Ascii => Ok(ascii(sub, &input)),
+        Command::Reverse => Ok(reverse(&input)),
+        Command::ReverseWords => Ok(reverse_words(&input)),
+        Command::Wrap => wrap(sub, &input),
+        Command::Fold => fold(sub, &input),
+        Command::NumWords => Ok(numwords::numwords(&input)),
+        Command::WordCount => Ok(word_count(&input).to_string()),
+        Command::Measure => Ok(measure(&input)),
+        Command::ReadTime => read_time(sub, &input),
+        Command::CharFreq => Ok(char_freq(&input)),
+        Command::WordFreq => Ok(word_freq(sub, &input)),
+        Command::SortLines => Ok(sort_lines(sub, &input)),
+        Command::Expand => expand(sub, &input),
+        Command::Unexpand => unexpand(sub, &input),
+        Command::Base64Encode => Ok(base64::engine::general_purpose::STANDARD.encode(&input)),
+        Command::Base64Decode => base64_decode(&input),
+        Command::Base32Encode => Ok(data_encoding::BASE32.encode(input.as_bytes())),
+        Command::Base32Decode => base32_decode(sub, &input),
+        Command::Rot13 => Ok(rot13(&input)),
+        Command::Cstr => cstr(sub, &input),
+        Command::Quote => Ok(quote(sub, &input)),
+        Command::Unquote => Ok(unquote(sub, &input)),
+        Command::Affix => affix_lines(sub, &input),
+        Command::Align => align(sub, &input),
+        Command::FilterChars => filter_chars(sub, &input),
+        Command::Mask => mask(sub, &input),
+        Command::Demojibake => Ok(fix_encoding::demojibake(&input)),
+        Command::Histogram => chart::histogram(sub, &input),
+        Command::HtmlEscape => Ok(html_escape(&input)),
+        Command::HtmlUnescape => Ok(html_unescape(&input)),
+        Command::Banner => Ok(banner(&input)),
+        Command::CommentBox => comment_box(sub, &input),
+        Command::Lorem => generate::lorem(sub),
+        Command::JsonPretty => json::pretty(sub, &input),
+        Command::JsonCompact => json::compact(&input),
+        Command::JsonGet => json::get(sub, &input),
+        Command::Toc => Ok(markdown::toc(&input)),
+        Command::DetectLang => Ok(lang::detect_lang(&input)),
+        Command::ImageInfo => image_info::image_info(sub),
+        Command::Csv => csv_utils::process_csv(sub, input),
+        Command::CsvCheck => csv_utils::check_csv(sub, input),
+        Command::Extract => extract::extract(sub, &input),
+        Command::GrepCount => grep::grep_count(sub, &input),
+        Command::Redact => redact::redact(sub, &input),
+        Command::Diff => diff::diff(sub, &input),
+        Command::WordDiff => diff::word_diff(sub, &input),
+        Command::Distance => distance::distance(sub, &input),
+        Command::Hash => hash::hash(sub, &input),
+        Command::Pipe => pipe::pipe(sub, &input),
+        Command::Qr => qr::qr(sub, &input),
+        Command::Timestamp => time::timestamp(sub, &input),
+        Command::ProseStats => Ok(prose_stats(&input)),
+        Command::Ruler => Ok(ruler(&input)),
+        Command::Codeblock => markdown::codeblock(sub, &input),
+        Command::NormalizeList => extract::normalize_list(sub, &input),
+        Command::Tokens => Ok(tokens(sub, &input)),
+        Command::When => when(sub, &input),
+        Command::Rtrim => Ok(rtrim_lines(input)),
+        Command::EnsureFinalNewline => Ok(ensure_final_newline(input)),
+        Command::Fit => fit(sub, &input),
+        Command::CsvDialect => csv_utils::dialect(sub, input),
+        Command::Verify => verify(sub, &input),
+        Command::StripComments => code::strip_comments(sub, &input),
+        Command::GenId => generate::gen_id(sub, &input),
+        Command::Reindent => code::reindent(sub, &input),
+        Command::Head => head_lines(sub, &input),
+        Command::Tail => tail_lines(sub, &input),
+        Command::DumbQuotes => Ok(dumb_quotes(&input)),
+        Command::SmartQuotes => Ok(smart_quotes(&input)),
+    }
+}
+
+/// Dash-separated slug. The `slug` crate always lowercases, so
+/// `case:keep` reimplements the transliterate-and-dash logic while
+/// preserving the original letter case.
+fn slugify(sub: &SubCommand, input: &str) -> Result<String, TransformError> {
+    match sub.get("case") {
+        None | Some("lower") => Ok(slug::slugify(input)),
+        Some("keep") => Ok(slugify_keep_case(input)),
+        Some(other) => Err(TransformError::InvalidArguments(format!(
+            "case must be keep or lower, got '{other}'"
+        ))),
+    }
+}
+
+fn slugify_keep_case(input: &str) -> String {
+    let mut out = String::new();
+    let mut pending_dash = false;
+    for c in deunicode::deunicode(input).chars() {
+        if c.is_ascii_alphanumeric() {
+            if pending_dash && !out.is_empty() {
+                out.push('-');
+            }
+            out.push(c);
+            pending_dash = false;
+        } else {
+            // Runs of separators collapse into one dash, and leading or
+            // trailing ones disappear.
+            pending_dash = true;
+        }
+    }
+    out
+}
+
+/// Transliterates the input to its closest ASCII equivalent (é→e, ß→ss)
+/// while keeping spaces and case, unlike `slugify`. Characters with no
+/// sensible mapping become `?`, or are dropped with `drop:true`.
+fn ascii(sub: &SubCommand, input: &str) -> String {
+    let placeholder = if sub.get_bool("drop") { "" } else { "?" };
+    deunicode::deunicode_with_tofu(input, placeholder)
+}
+
+/// Reverses the input grapheme cluster by grapheme cluster, so that
+/// combining characters and emoji survive the trip.
+fn reverse(input: &str) -> String {
+    input.graphemes(true).rev().collect()
+}
+
+/// Reverses the order of whitespace-separated words, leaving each
+/// word's characters intact. Runs of whitespace collapse to a single
+/// space on the way through.
+fn reverse_words(input: &str) -> String {
+    input.split_whitespace().rev().collect::<Vec<&str>>().join(" ")
+}
+
+/// Greedy word wrap at the width given by `w:<n>` (default 80).
+fn wrap(sub: &SubCommand, input: &str) -> Result<String, TransformError> {
+    let width: usize = sub.get_parsed("w")?.unwrap_or(80);
+    if width == 0 {
+        return Err(TransformError::InvalidArguments(
+            "wrap width must be positive".to_string(),
+        ));
+    }
+
+    let mut out = String::new();
+    for line in input.lines() {
+        let mut current = 0usize;
+        for word in line.split_whitespace() {
+            let len = word.graphemes(true).count();
+            if current == 0 {
+                out.push_str(word);
+                current = len;
+            } else if current + 1 + len <= width {
+                out.push(' ');
+                out.push_str(word);
+                current += 1 + len;
+            } else {
+                out.push('\n');
+                out.push_str(word);
+                current = len;
+            }
+        }
+        out.push('\n');
+    }
+    if out.ends_with('\n') {
+        out.pop();
+    }
+    Ok(out)
+}
+
+/// Like `wrap`, but words longer than the width are hyphenated at the
+/// break instead of overflowing. Splitting is purely character-based
+/// (grapheme clusters, no dictionary).
+fn fold(sub: &SubCommand, input: &str) -> Result<String, TransformError> {
+    let width: usize = sub.get_parsed("w")?.unwrap_or(80);
+    if width < 2 {
+        return Err(TransformError::InvalidArguments(
+            "fold width must be at least 2".to_string(),
+        ));
+    }
+
+    let mut out = String::new();
+    for line in input.lines() {
+        let mut current = 0usize;
+        for word in line.split_whitespace() {
+            let graphemes: Vec<&str> = word.graphemes(true).collect();
+            if graphemes.len() <= width {
+                if current == 0 {
+                    // start of line, nothing to do
+                } else if current + 1 + graphemes.len() <= width {
+                    out.push(' ');
+                    current += 1;
+                } else {
+                    out.push('\n');
+                    current = 0;
+                }
+                out.push_str(word);
+                current += graphemes.len();
+            } else {
+                // Long word: flush the current line, then emit hyphenated
+                // chunks of width - 1 graphemes.
+                if current > 0 {
+                    out.push('\n');
+                }
+                let mut rest = graphemes.as_slice();
+                while rest.len() > width {
+                    let (chunk, tail) = rest.split_at(width - 1);
+                    out.push_str(&chunk.concat());
+                    out.push('-');
+                    out.push('\n');
+                    rest = tail;
+                }
+                out.push_str(&rest.concat());
+                current = rest.len();
+            }
+        }
+        out.push('\n');
+    }
+    out.pop();
+    Ok(out)
+}
+
+pub fn word_count(input: &str) -> usize {
+    input.split_whitespace().count()
+}
+
+/// Reports the input's size as bytes, chars, and grapheme clusters —
+/// three counts that only coincide for plain ASCII.
+fn measure(input: &str) -> String {
+    format!(
+        "bytes: {}  chars: {}  graphemes: {}",
+        input.len(),
+        input.chars().count(),
+        input.graphemes(true).count()
+    )
+}
+
+/// Checks the input against `limit:<n>` and, when it is too long,
+/// splits it into numbered chunks — `chunk text (1/3)` — each within
+/// the limit, breaking at word boundaries (a single over-long word is
+/// hard-split). Lengths count grapheme clusters, matching how posting
+/// sites count "characters". Input already within the limit passes
+/// through untouched, one chunk per output line otherwise.
+fn fit(sub: &SubCommand, input: &str) -> Result<String, TransformError> {
+    let limit: usize = sub
+        .get_parsed("limit")?
+        .ok_or_else(|| TransformError::InvalidArguments("fit requires limit:<n>".to_string()))?;
+    let text = input.trim();
+    if text.graphemes(true).count() <= limit {
+        return Ok(text.to_string());
+    }
+
+    // The marker length depends on the chunk count, which depends on
+    // the marker length; re-chunk until the count stops growing.
+    let mut marker_len = " (1/1)".len();
+    loop {
+        let budget = limit.checked_sub(marker_len).ok_or_else(|| {
+            TransformError::InvalidArguments(format!(
+                "limit {limit} is too small for a chunk marker"
+            ))
+        })?;
+        let chunks = chunk_words(text, budget);
+        let total = chunks.len();
+        let widest = format!(" ({total}/{total})").len();
+        if widest > marker_len {
+            marker_len = widest;
+            continue;
+        }
+        let numbered: Vec<String> = chunks
+            .iter()
+            .enumerate()
+            .map(|(i, chunk)| format!("{chunk} ({}/{total})", i + 1))
+            .collect();
+        return Ok(numbered.join("\n"));
+    }
+}
+
+/// Greedily packs whole words into chunks of at most `budget`
+/// graphemes; a word longer than the budget is split mid-word.
+fn chunk_words(text: &str, budget: usize) -> Vec<String> {
+    let mut chunks: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut current_len = 0;
+    for word in text.split_whitespace() {
+        let mut word_len = word.graphemes(true).count();
+        let mut word = word;
+        while word_len > budget {
+            // Hopeless word: hard-split off what fits.
+            if current_len > 0 {
+                chunks.push(std::mem::take(&mut current));
+                current_len = 0;
+            }
+            let cut: usize = word.graphemes(true).take(budget).map(str::len).sum();
+            chunks.push(word[..cut].to_string());
+            word = &word[cut..];
+            word_len -= budget;
+        }
+        let needed = word_len + usize::from(current_len > 0);
+        if current_len + needed > budget && current_len > 0 {
+            chunks.push(std::mem::take(&mut current));
+            current_len = 0;
+        }
+        if current_len > 0 {
+            current.push(' ');
+            current_len += 1;
+        }
+        current.push_str(word);
+        current_len += word_len;
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// Strips trailing whitespace from every line, leaving leading
+/// indentation alone — the classic formatter cleanup.
+fn rtrim_lines(s: String) -> String {
+    s.lines()
+        .map(str::trim_end)
+        .collect::<Vec<&str>>()
+        .join("\n")
+}
+
+/// Makes the input end with exactly one newline: a missing one is
+/// added, a run of blank trailing lines collapses to one terminator.
+fn ensure_final_newline(s: String) -> String {
+    format!("{}\n", s.trim_end_matches('\n'))
+}
+
+/// How many lines `head` and `tail` keep when `n:<count>` is absent,
+/// matching the Unix tools.
+const HEAD_TAIL_DEFAULT: usize = 10;
+
+/// First `n:<count>` lines of the input.
+fn head_lines(sub: &SubCommand, input: &str) -> Result<String, TransformError> {
+    let n = sub.get_parsed("n")?.unwrap_or(HEAD_TAIL_DEFAULT);
+    Ok(input.lines().take(n).collect::<Vec<&str>>().join("\n"))
+}
+
+/// Last `n:<count>` lines of the input. A bounded ring buffer holds only
+/// the current candidates, so memory stays constant when the input is
+/// piped in from something long-running.
+fn tail_lines(sub: &SubCommand, input: &str) -> Result<String, TransformError> {
+    let n = sub.get_parsed("n")?.unwrap_or(HEAD_TAIL_DEFAULT);
+    let mut ring: VecDeque<&str> = VecDeque::with_capacity(n + 1);
+    for line in input.lines() {
+        ring.push_back(line);
+        if ring.len() > n {
+            ring.pop_front();
+        }
+    }
+    Ok(ring.into_iter().collect::<Vec<&str>>().join("\n"))
+}
+
+/// Replaces typographic punctuation with its ASCII equivalent: curly
+/// quotes become straight ones, em and en dashes become `--` and `-`,
+/// and the ellipsis character becomes `...`.
+fn dumb_quotes(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for c in input.chars() {
+        match c {
+            '\u{201C}' | '\u{201D}' => out.push('"'),
+            '\u{2018}' | '\u{2019}' => out.push('\''),
+            '\u{2014}' => out.push_str("--"),
+            '\u{2013}' => out.push('-'),
+            '\u{2026}' => out.push_str("..."),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// The inverse of [`dumb_quotes`]: `--` becomes an em dash, `...` an
+/// ellipsis, and straight quotes become curly ones — opening after
+/// whitespace, an opening bracket, or the start of input, closing
+/// everywhere else. An apostrophe inside a word lands on the closing
+/// single quote, which is also the typographic apostrophe.
+fn smart_quotes(input: &str) -> String {
+    let expanded = input.replace("...", "\u{2026}").replace("--", "\u{2014}");
+
+    let mut out = String::with_capacity(expanded.len());
+    let mut prev: Option<char> = None;
+    for c in expanded.chars() {
+        let replaced = match c {
+            '"' | '\'' => {
+                let opening = prev.is_none_or(|p| {
+                    p.is_whitespace() || matches!(p, '(' | '[' | '{' | '\u{201C}' | '\u{2018}')
+                });
+                match (c, opening) {
+                    ('"', true) => '\u{201C}',
+                    ('"', false) => '\u{201D}',
+                    ('\'', true) => '\u{2018}',
+                    _ => '\u{2019}',
+                }
+            }
+            c => c,
+        };
+        out.push(replaced);
+        prev = Some(replaced);
+    }
+    out
+}
+
+/// Meta-command: runs `forward:<cmd>` then `inverse:<cmd>` over the
+/// input and checks the round trip reproduces it, for exercising the
+/// encode/decode and cipher pairs. A lossy pair (or a typo'd inverse)
+/// is an error, so scripted checks exit non-zero on mismatch.
+fn verify(sub: &SubCommand, input: &str) -> Result<String, TransformError> {
+    let forward = sub.get("forward").ok_or_else(|| {
+        TransformError::InvalidArguments("verify requires forward:<command>".to_string())
+    })?;
+    let inverse = sub.get("inverse").ok_or_else(|| {
+        TransformError::InvalidArguments("verify requires inverse:<command>".to_string())
+    })?;
+
+    let registry = Registry::new();
+    let inner = SubCommand::default();
+    let encoded = registry.transmute(forward, &inner, input.to_string())?;
+    let round_tripped = registry.transmute(inverse, &inner, encoded)?;
+    if round_tripped == input {
+        Ok(format!("round-trip ok: {forward} then {inverse}"))
+    } else {
+        Err(TransformError::Other(format!(
+            "round-trip mismatch: {forward} then {inverse} produced {round_tripped:?}"
+        )))
+    }
+}
+
+/// Meta-command: applies `then:<command>` only to the lines matching
+/// `match:<regex>`, through the same registry the CLI dispatches
+/// through, so any transform (or custom registration) works. Lines
+/// that do not match pass through verbatim.
+fn when(sub: &SubCommand, input: &str) -> Result<String, TransformError> {
+    let pattern = sub.get("match").ok_or_else(|| {
+        TransformError::InvalidArguments("when requires match:<regex>".to_string())
+    })?;
+    let then = sub.get("then").ok_or_else(|| {
+        TransformError::InvalidArguments("when requires then:<command>".to_string())
+    })?;
+    let re = regex::Regex::new(pattern)
+        .map_err(|e| TransformError::InvalidArguments(format!("invalid regex: {e}")))?;
+
+    let registry = Registry::new();
+    let inner = SubCommand::default();
+    let lines: Vec<String> = input
+        .lines()
+        .map(|line| {
+            if re.is_match(line) {
+                registry.transmute(then, &inner, line.to_string())
+            } else {
+                Ok(line.to_string())
+            }
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(lines.join("\n"))
+}
+
+/// Splits the whole input on whitespace and re-emits the tokens
+/// space-separated, or one per line with `lines:true`. `sort:true`
+/// orders them, `unique:true` drops repeats (keeping first occurrence
+/// when unsorted). Handy for cleaning word lists, where line sorting
+/// is the wrong granularity.
+fn tokens(sub: &SubCommand, input: &str) -> String {
+    let mut tokens: Vec<&str> = input.split_whitespace().collect();
+    if sub.get_bool("sort") {
+        tokens.sort_unstable();
+    }
+    if sub.get_bool("unique") {
+        let mut seen = std::collections::HashSet::new();
+        tokens.retain(|token| seen.insert(*token));
+    }
+    let separator = if sub.get_bool("lines") { "\n" } else { " " };
+    tokens.join(separator)
+}
+
+/// Prints a column ruler above the input for width debugging: a units
+/// line of `1234567890` repeating, sized to the widest input line, with
+/// a tens line above it once the input is at least ten columns wide.
+/// Columns are counted in chars, like `wrap` and `fold`.
+fn ruler(input: &str) -> String {
+    let width = input.lines().map(|line| line.chars().count()).max().unwrap_or(0);
+
+    let units: String = (1..=width).map(|i| char::from(b'0' + (i % 10) as u8)).collect();
+    if width < 10 {
+        return format!("{units}\n{input}");
+    }
+    let tens: String = (1..=width)
+        .map(|i| {
+            if i % 10 == 0 {
+                char::from(b'0' + (i / 10 % 10) as u8)
+            } else {
+                ' '
+            }
+        })
+        .collect();
+    format!("{tens}\n{units}\n{input}")
+}
+
+/// Writing-analysis summary: paragraph count, sentence count, average
+/// sentence length in words, and the longest sentence. Paragraphs are
+/// blank-line-separated blocks; sentences end at `.`, `?`, or `!`.
+/// Abbreviations like "e.g." or "Dr." also end on a period, so prose
+/// full of them over-counts sentences and deflates the average — this
+/// splitter makes no attempt to recognize them.
+fn prose_stats(input: &str) -> String {
+    let paragraphs = input
+        .split("\n\n")
+        .filter(|block| !block.trim().is_empty())
+        .count();
+
+    let lengths: Vec<usize> = input
+        .split(['.', '?', '!'])
+        .map(word_count)
+        .filter(|&words| words > 0)
+        .collect();
+    let sentences = lengths.len();
+    let longest = lengths.iter().copied().max().unwrap_or(0);
+    let average = if sentences == 0 {
+        0.0
+    } else {
+        lengths.iter().sum::<usize>() as f64 / sentences as f64
+    };
+
+    format!(
+        "paragraphs: {paragraphs}  sentences: {sentences}  avg sentence: {average:.1} words  longest: {longest} words"
+    )
+}
+
+/// Estimates reading time from the word count, e.g. `~3 min (612 words)`.
+/// Reading speed comes from `wpm:<n>` (default 200). Words are counted
+/// exactly like the `wordcount` command.
+fn read_time(sub: &SubCommand, input: &str) -> Result<String, TransformError> {
+    let wpm: usize = sub.get_parsed("wpm")?.unwrap_or(200);
+    if wpm == 0 {
+        return Err(TransformError::InvalidArguments(
+            "wpm must be positive".to_string(),
+        ));
+    }
+
+    let words = word_count(input);
+    let minutes = ((words + wpm / 2) / wpm).max(usize::from(words > 0));
+    Ok(format!("~{minutes} min ({words} words)"))
+}
+
+fn char_freq(input: &str) -> String {
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    for c in input.chars().filter(|c| !c.is_whitespace()) {
+        *counts.entry(c).or_insert(0) += 1;
+    }
+    let mut entries: Vec<(char, usize)> = counts.into_iter().collect();
+    entries.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    let mut out = String::new();
+    for (c, n) in entries {
+        let _ = writeln!(out, "{c}: {n}");
+    }
+    out.pop();
+    out
+}
+
+/// Counts words (tokenized exactly like `wordcount`) and lists them by
+/// descending count as `word: n` lines, ties broken alphabetically.
+/// `lower:true` folds case first; `strip-punct:true` trims punctuation
+/// from the edges of each token.
+fn word_freq(sub: &SubCommand, input: &str) -> String {
+    let lower = sub.get_bool("lower");
+    let strip_punct = sub.get_bool("strip-punct");
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for token in input.split_whitespace() {
+        let mut word = if strip_punct {
+            token.trim_matches(|c: char| c.is_ascii_punctuation()).to_string()
+        } else {
+            token.to_string()
+        };
+        if lower {
+            word = word.to_lowercase();
+        }
+        if word.is_empty() {
+            continue;
+        }
+        *counts.entry(word).or_insert(0) += 1;
+    }
+
+    let mut entries: Vec<(String, usize)> = counts.into_iter().collect();
+    entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    let mut out = String::new();
+    for (word, n) in entries {
+        let _ = writeln!(out, "{word}: {n}");
+    }
+    out.pop();
+    out
+}
+
+fn sort_lines(sub: &SubCommand, input: &str) -> String {
+    let mut lines: Vec<&str> = input.lines().collect();
+    if sub.ignore_case {
+        lines.sort_by_key(|line| line.to_lowercase());
+    } else {
+        lines.sort_unstable();
+    }
+    lines.join("\n")
+}
+
+/// Replaces tabs with spaces, keeping columns aligned to the tab stop
+/// given by `n:<width>` (default 4).
+fn expand(sub: &SubCommand, input: &str) -> Result<String, TransformError> {
+    let tab_width: usize = sub.get_parsed("n")?.unwrap_or(4);
+    if tab_width == 0 {
+        return Err(TransformError::InvalidArguments(
+            "tab width must be positive".to_string(),
+        ));
+    }
+
+    let mut out = String::new();
+    for line in input.lines() {
+        let mut col = 0usize;
+        for c in line.chars() {
+            if c == '\t' {
+                let pad = tab_width - (col % tab_width);
+                for _ in 0..pad {
+                    out.push(' ');
+                }
+                col += pad;
+            } else {
+                out.push(c);
+                col += 1;
+            }
+        }
+        out.push('\n');
+    }
+    out.pop();
+    Ok(out)
+}
+
+/// Replaces leading runs of spaces with tabs at the given tab stop.
+fn unexpand(sub: &SubCommand, input: &str) -> Result<String, TransformError> {
+    let tab_width: usize = sub.get_parsed("n")?.unwrap_or(4);
+    if tab_width == 0 {
+        return Err(TransformError::InvalidArguments(
+            "tab width must be positive".to_string(),
+        ));
+    }
+
+    let mut out = String::new();
+    for line in input.lines() {
+        let leading = line.len() - line.trim_start_matches(' ').len();
+        for _ in 0..leading / tab_width {
+            out.push('\t');
+        }
+        for _ in 0..leading % tab_width {
+            out.push(' ');
+        }
+        out.push_str(line.trim_start_matches(' '));
+        out.push('\n');
+    }
+    out.pop();
+    Ok(out)
+}
+
+fn base64_decode(input: &str) -> Result<String, TransformError> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(input.trim())
+        .map_err(|e| TransformError::InvalidArguments(format!("invalid base64: {e}")))?;
+    String::from_utf8(bytes)
+        .map_err(|e| TransformError::Other(format!("decoded data is not UTF-8: {e}")))
+}
+
+/// Decodes RFC 4648 base32. Input case does not matter; with
+/// `loose:true`, missing `=` padding is tolerated as well.
+fn base32_decode(sub: &SubCommand, input: &str) -> Result<String, TransformError> {
+    let normalized = input.trim().to_ascii_uppercase();
+    let bytes = if sub.get_bool("loose") {
+        data_encoding::BASE32_NOPAD.decode(normalized.trim_end_matches('=').as_bytes())
+    } else {
+        data_encoding::BASE32.decode(normalized.as_bytes())
+    }
+    .map_err(|e| TransformError::InvalidArguments(format!("invalid base32: {e}")))?;
+    String::from_utf8(bytes)
+        .map_err(|e| TransformError::Other(format!("decoded data is not UTF-8: {e}")))
+}
+
+fn rot13(input: &str) -> String {
+    input
+        .chars()
+        .map(|c| match c {
+            'a'..='z' => (b'a' + (c as u8 - b'a' + 13) % 26) as char,
+            'A'..='Z' => (b'A' + (c as u8 - b'A' + 13) % 26) as char,
+            other => other,
+        })
+        .collect()
+}
+
+/// Escapes the input as a double-quoted string literal, for embedding
+/// in source code. `lang:rust` (default) escapes non-printables as
+/// `\u{...}`; `lang:c` escapes them byte-by-byte as `\xNN`.
+fn cstr(sub: &SubCommand, input: &str) -> Result<String, TransformError> {
+    let lang = sub.get("lang").unwrap_or("rust");
+    if !matches!(lang, "rust" | "c") {
+        return Err(TransformError::InvalidArguments(format!(
+            "unknown lang '{lang}', expected rust or c"
+        )));
+    }
+
+    let mut out = String::from("\"");
+    for c in input.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            ' '..='~' => out.push(c),
+            c if lang == "rust" => {
+                let _ = write!(out, "\\u{{{:x}}}", c as u32);
+            }
+            c => {
+                let mut buf = [0u8; 4];
+                for byte in c.encode_utf8(&mut buf).bytes() {
+                    let _ = write!(out, "\\x{byte:02x}");
+                }
+            }
+        }
+    }
+    out.push('"');
+    Ok(out)
+}
+
+/// Prefixes every line with `> ` (or the `prefix:<str>` argument),
+/// adding one quote level. Already-quoted lines gain another level.
+fn quote(sub: &SubCommand, input: &str) -> String {
+    let prefix = sub.get("prefix").unwrap_or("> ");
+    let lines: Vec<String> = input
+        .lines()
+        .map(|line| format!("{prefix}{line}"))
+        .collect();
+    lines.join("\n")
+}
+
+/// Strips exactly one level of the quote prefix; unquoted lines pass
+/// through unchanged.
+fn unquote(sub: &SubCommand, input: &str) -> String {
+    let prefix = sub.get("prefix").unwrap_or("> ");
+    let lines: Vec<&str> = input
+        .lines()
+        .map(|line| line.strip_prefix(prefix).unwrap_or(line))
+        .collect();
+    lines.join("\n")
+}
+
+/// Adds `prefix:<s>` and/or `suffix:<s>` to every line; either may be
+/// empty or absent. Blank lines are affixed too unless `blank:skip`
+/// leaves them untouched.
+fn affix_lines(sub: &SubCommand, input: &str) -> Result<String, TransformError> {
+    let prefix = sub.get("prefix").unwrap_or("");
+    let suffix = sub.get("suffix").unwrap_or("");
+    let skip_blank = match sub.get("blank") {
+        None | Some("affix") => false,
+        Some("skip") => true,
+        Some(other) => {
+            return Err(TransformError::InvalidArguments(format!(
+                "blank must be skip or affix, got '{other}'"
+            )))
+        }
+    };
+
+    let lines: Vec<String> = input
+        .lines()
+        .map(|line| {
+            if skip_blank && line.trim().is_empty() {
+                line.to_string()
+            } else {
+                format!("{prefix}{line}{suffix}")
+            }
+        })
+        .collect();
+    Ok(lines.join("\n"))
+}
+
+/// Filters characters by Unicode class: `keep:alpha,digit` retains only
+/// the listed classes, `strip:punct,space` removes them. Exactly one of
+/// the two must be given.
+fn filter_chars(sub: &SubCommand, input: &str) -> Result<String, TransformError> {
+    let (spec, keep) = match (sub.get("keep"), sub.get("strip")) {
+        (Some(_), Some(_)) => {
+            return Err(TransformError::InvalidArguments(
+                "keep and strip are mutually exclusive".to_string(),
+            ))
+        }
+        (Some(spec), None) => (spec, true),
+        (None, Some(spec)) => (spec, false),
+        (None, None) => {
+            return Err(TransformError::InvalidArguments(
+                "filter-chars requires keep:<classes> or strip:<classes>".to_string(),
+            ))
+        }
+    };
+
+    let classes: Vec<fn(char) -> bool> = spec
+        .split(',')
+        .map(char_class)
+        .collect::<Result<_, _>>()?;
+    Ok(input
+        .chars()
+        .filter(|&c| classes.iter().any(|matches| matches(c)) == keep)
+        .collect())
+}
+
+fn char_class(name: &str) -> Result<fn(char) -> bool, TransformError> {
+    match name.trim() {
+        "alpha" => Ok(char::is_alphabetic),
+        "digit" => Ok(char::is_numeric),
+        "space" => Ok(char::is_whitespace),
+        "punct" => Ok(|c| c.is_ascii_punctuation()),
+        "control" => Ok(char::is_control),
+        other => Err(TransformError::InvalidArguments(format!(
+            "unknown character class '{other}', expected alpha, digit, space, punct, or control"
+        ))),
+    }
+}
+
+/// Masks all but the last `keep:<n>` (default 4) grapheme clusters of
+/// each line with `char:<c>` (default `*`), for showing partial secrets
+/// like card numbers or tokens.
+fn mask(sub: &SubCommand, input: &str) -> Result<String, TransformError> {
+    let keep: usize = sub.get_parsed("keep")?.unwrap_or(4);
+    let mask_char = match sub.get("char") {
+        None => '*',
+        Some(s) => {
+            let mut chars = s.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => c,
+                _ => {
+                    return Err(TransformError::InvalidArguments(format!(
+                        "char must be a single character, got '{s}'"
+                    )))
+                }
+            }
+        }
+    };
+
+    let lines: Vec<String> = input
+        .lines()
+        .map(|line| {
+            let graphemes: Vec<&str> = line.graphemes(true).collect();
+            let visible = keep.min(graphemes.len());
+            let mut out = mask_char.to_string().repeat(graphemes.len() - visible);
+            out.push_str(&graphemes[graphemes.len() - visible..].concat());
+            out
+        })
+        .collect();
+    Ok(lines.join("\n"))
+}
+
+/// Aligns the first `sep:<char>` (default `=`) across all lines by
+/// padding the key side, so config-style `key=value` input reads as a
+/// neat column. Lines without the separator pass through unchanged.
+fn align(sub: &SubCommand, input: &str) -> Result<String, TransformError> {
+    let sep = match sub.get("sep") {
+        None => '=',
+        Some(s) => {
+            let mut chars = s.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => c,
+                _ => {
+                    return Err(TransformError::InvalidArguments(format!(
+                        "sep must be a single character, got '{s}'"
+                    )))
+                }
+            }
+        }
+    };
+
+    let key_width = input
+        .lines()
+        .filter_map(|line| line.split_once(sep))
+        .map(|(key, _)| key.trim_end().chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let lines: Vec<String> = input
+        .lines()
+        .map(|line| match line.split_once(sep) {
+            Some((key, value)) => {
+                let key = key.trim_end();
+                let pad = " ".repeat(key_width - key.chars().count());
+                format!("{key}{pad} {sep} {}", value.trim_start())
+            }
+            None => line.to_string(),
+        })
+        .collect();
+    Ok(lines.join("\n"))
+}
+
+/// Escapes the five HTML-special characters (`& < > " '`) as named or
+/// numeric entities, leaving everything else alone.
+fn html_escape(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for c in input.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Decodes named (`&amp;`), decimal (`&#38;`), and hex (`&#x26;`)
+/// entities. Anything that does not parse as an entity — including a
+/// bare `&` — is passed through untouched.
+fn html_unescape(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(pos) = rest.find('&') {
+        out.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+        let decoded = rest[1..]
+            .find(';')
+            .and_then(|end| decode_entity(&rest[1..end + 1]).map(|c| (c, end + 2)));
+        match decoded {
+            Some((c, consumed)) => {
+                out.push(c);
+                rest = &rest[consumed..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn decode_entity(entity: &str) -> Option<char> {
+    match entity {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        _ => {
+            let num = entity.strip_prefix('#')?;
+            let code = match num.strip_prefix('x').or_else(|| num.strip_prefix('X')) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => num.parse().ok()?,
+            };
+            char::from_u32(code)
+        }
+    }
+}
+
+/// Box rows shared by `banner` and `comment-box`: a `*` border, one
+/// `* line *` row per input line, and a closing border, all sized to
+/// the widest line.
+fn box_lines(input: &str) -> Vec<String> {
+    let lines: Vec<&str> = input.lines().collect();
+    let width = lines
+        .iter()
+        .map(|l| l.graphemes(true).count())
+        .max()
+        .unwrap_or(0);
+
+    let border = "*".repeat(width + 4);
+    let mut rows = vec![border.clone()];
+    for line in &lines {
+        let pad = width - line.graphemes(true).count();
+        rows.push(format!("* {}{} *", line, " ".repeat(pad)));
+    }
+    rows.push(border);
+    rows
+}
+
+/// Draws a box of `*` around the input, sized to the widest line.
+fn banner(input: &str) -> String {
+    box_lines(input).join("\n")
+}
+
+/// Wraps the input in a language-appropriate comment block: a `/* */`
+/// box for `lang:c` (the default), or the banner box behind `//` or `#`
+/// line comments for `lang:rust` and `lang:py`.
+fn comment_box(sub: &SubCommand, input: &str) -> Result<String, TransformError> {
+    let rows = box_lines(input);
+    let prefixed = |prefix: &str| -> String {
+        rows.iter()
+            .map(|row| format!("{prefix}{row}"))
+            .collect::<Vec<String>>()
+            .join("\n")
+    };
+    match sub.get("lang").unwrap_or("c") {
+        "c" => {
+            let mut out = vec![format!("/{}", rows[0])];
+            for row in &rows[1..rows.len() - 1] {
+                out.push(format!(" {row}"));
+            }
+            let closing = &rows[rows.len() - 1];
+            out.push(format!(" {}/", &closing[..closing.len() - 1]));
+            Ok(out.join("\n"))
+        }
+        "rust" => Ok(prefixed("// ")),
+        "py" => Ok(prefixed("# ")),
+        other => Err(TransformError::InvalidArguments(format!(
+            "lang must be c, rust, or py, got '{other}'"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn no_args() -> SubCommand {
+        SubCommand::default()
+    }
+
+    #[test]
+    fn ascii_transliterates_accents() {
+        let out = transmute(Command::Ascii, &no_args(), "Crème Brûlée".to_string()).unwrap();
+        assert_eq!(out, "Creme Brulee");
+    }
+
+    #[test]
+    fn reverse_is_grapheme_aware() {
+        let out = transmute(Command::Reverse, &no_args(), "ab🦀é".to_string()).unwrap();
+        assert_eq!(out, "é🦀ba");
+    }
+
+    #[test]
+    fn reverse_words_keeps_characters_in_order() {
+        let out = transmute(
+            Command::ReverseWords,
+            &no_args(),
+            "one two three".to_string(),
+        )
+        .unwrap();
+        assert_eq!(out, "three two one");
+    }
+
+    #[test]
+    fn wrap_respects_width() {
+        let sub = SubCommand::parse(&["w:10".to_string()]).unwrap();
+        let out = transmute(
+            Command::Wrap,
+            &sub,
+            "one two three four five".to_string(),
+        )
+        .unwrap();
+        for line in out.lines() {
+            assert!(line.chars().count() <= 10, "line too long: {line}");
+        }
+    }
+
+    #[test]
+    fn fold_hyphenates_long_words() {
+        let sub = SubCommand::parse(&["w:8".to_string()]).unwrap();
+        let word = "a".repeat(20);
+        let out = transmute(Command::Fold, &sub, word).unwrap();
+        assert_eq!(out, "aaaaaaa-\naaaaaaa-\naaaaaa");
+        for line in out.lines() {
+            assert!(line.chars().count() <= 8, "line too long: {line}");
+        }
+    }
+
+    #[test]
+    fn fold_leaves_short_words_alone() {
+        let sub = SubCommand::parse(&["w:10".to_string()]).unwrap();
+        let out = transmute(Command::Fold, &sub, "tiny words here".to_string()).unwrap();
+        assert_eq!(out, "tiny words\nhere");
+    }
+
+    #[test]
+    fn wordcount_splits_on_whitespace() {
+        assert_eq!(word_count("one  two\tthree\nfour"), 4);
+    }
+
+    #[test]
+    fn word_freq_ranks_by_count_then_alphabetically() {
+        let sub = SubCommand::parse(&["lower:true".to_string(), "strip-punct:true".to_string()])
+            .unwrap();
+        let out = transmute(
+            Command::WordFreq,
+            &sub,
+            "The cat saw the dog, and the dog saw The cat.".to_string(),
+        )
+        .unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines[0], "the: 4");
+        assert_eq!(&lines[1..4], &["cat: 2", "dog: 2", "saw: 2"]);
+        assert_eq!(lines[4], "and: 1");
+    }
+
+    #[test]
+    fn readtime_estimates_minutes() {
+        let text = "word ".repeat(612);
+        let out = transmute(Command::ReadTime, &no_args(), text).unwrap();
+        assert_eq!(out, "~3 min (612 words)");
+
+        let sub = SubCommand::parse(&["wpm:100".to_string()]).unwrap();
+        let out = transmute(Command::ReadTime, &sub, "word ".repeat(250)).unwrap();
+        assert_eq!(out, "~3 min (250 words)");
+    }
+
+    #[test]
+    fn base64_round_trip() {
+        let encoded =
+            transmute(Command::Base64Encode, &no_args(), "crab 🦀".to_string()).unwrap();
+        let decoded = transmute(Command::Base64Decode, &no_args(), encoded).unwrap();
+        assert_eq!(decoded, "crab 🦀");
+    }
+
+    #[test]
+    fn base32_round_trip() {
+        let encoded =
+            transmute(Command::Base32Encode, &no_args(), "crab 🦀".to_string()).unwrap();
+        let decoded = transmute(Command::Base32Decode, &no_args(), encoded).unwrap();
+        assert_eq!(decoded, "crab 🦀");
+    }
+
+    #[test]
+    fn base32_decode_tolerates_case_and_loose_padding() {
+        let out = transmute(Command::Base32Decode, &no_args(), "nbswy3dp".to_string()).unwrap();
+        assert_eq!(out, "hello");
+
+        // "hi" encodes to NBUQ==== — without padding only loose mode accepts it.
+        assert!(transmute(Command::Base32Decode, &no_args(), "NBUQ".to_string()).is_err());
+        let sub = SubCommand::parse(&["loose:true".to_string()]).unwrap();
+        let out = transmute(Command::Base32Decode, &sub, "NBUQ".to_string()).unwrap();
+        assert_eq!(out, "hi");
+
+        assert!(transmute(Command::Base32Decode, &no_args(), "not base32!".to_string()).is_err());
+    }
+
+    #[test]
+    fn cstr_escapes_quote_and_newline() {
+        let out = transmute(Command::Cstr, &no_args(), "say \"hi\"\nbye".to_string()).unwrap();
+        assert_eq!(out, r#""say \"hi\"\nbye""#);
+    }
+
+    #[test]
+    fn cstr_escapes_non_ascii_per_lang() {
+        let rust = transmute(Command::Cstr, &no_args(), "é".to_string()).unwrap();
+        assert_eq!(rust, r#""\u{e9}""#);
+
+        let sub = SubCommand::parse(&["lang:c".to_string()]).unwrap();
+        let c = transmute(Command::Cstr, &sub, "é".to_string()).unwrap();
+        assert_eq!(c, r#""\xc3\xa9""#);
+    }
+
+    #[test]
+    fn quote_round_trips_one_level() {
+        let input = "first\nsecond".to_string();
+        let quoted = transmute(Command::Quote, &no_args(), input.clone()).unwrap();
+        assert_eq!(quoted, "> first\n> second");
+        let back = transmute(Command::Unquote, &no_args(), quoted).unwrap();
+        assert_eq!(back, input);
+    }
+
+    #[test]
+    fn quote_nests_and_unquote_strips_one_level() {
+        let once = transmute(Command::Quote, &no_args(), "hi".to_string()).unwrap();
+        let twice = transmute(Command::Quote, &no_args(), once.clone()).unwrap();
+        assert_eq!(twice, "> > hi");
+        let back = transmute(Command::Unquote, &no_args(), twice).unwrap();
+        assert_eq!(back, once);
+    }
+
+    #[test]
+    fn json_envelope_shapes_success_and_failure() {
+        let ok = json_envelope("slugify", &Ok("hello-world".to_string()));
+        let value: serde_json::Value = serde_json::from_str(&ok).unwrap();
+        assert_eq!(value["command"], "slugify");
+        assert_eq!(value["ok"], true);
+        assert_eq!(value["output"], "hello-world");
+
+        let err = json_envelope(
+            "base64-decode",
+            &Err(TransformError::Other("bad input".to_string())),
+        );
+        let value: serde_json::Value = serde_json::from_str(&err).unwrap();
+        assert_eq!(value["command"], "base64-decode");
+        assert_eq!(value["ok"], false);
+        assert_eq!(value["error"], "bad input");
+    }
+
+    #[test]
+    fn timed_passes_the_result_through() {
+        let (result, elapsed) = timed(|| transmute(Command::Uppercase, &no_args(), "hi".into()));
+        assert_eq!(result.unwrap(), "HI");
+        assert!(elapsed >= std::time::Duration::ZERO);
+
+        let (result, _) = timed(|| transmute(Command::Base64Decode, &no_args(), "!".into()));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn registry_resolves_builtins_and_custom_transforms() {
+        struct Shout;
+        impl Transform for Shout {
+            fn name(&self) -> &str {
+                "shout"
+            }
+            fn apply(&self, _sub: &SubCommand, input: String) -> Result<String, TransformError> {
+                Ok(format!("{}!", input.to_uppercase()))
+            }
+        }
+
+        let mut registry = Registry::new();
+        registry.register(Box::new(Shout));
+
+        let out = registry
+            .transmute("shout", &no_args(), "hello".to_string())
+            .unwrap();
+        assert_eq!(out, "HELLO!");
+
+        let out = registry
+            .transmute("uppercase", &no_args(), "hello".to_string())
+            .unwrap();
+        assert_eq!(out, "HELLO");
+
+        assert!(matches!(
+            registry.transmute("florbify", &no_args(), String::new()),
+            Err(TransformError::InvalidCommand(_))
+        ));
+    }
+
+    #[test]
+    fn measure_distinguishes_bytes_chars_and_graphemes() {
+        // "café🦀" with a combining acute: é is 1 grapheme but 2 chars.
+        let input = "cafe\u{301}\u{1f980}".to_string();
+        let out = transmute(Command::Measure, &no_args(), input).unwrap();
+        assert_eq!(out, "bytes: 10  chars: 6  graphemes: 5");
+    }
+
+    #[test]
+    fn verify_confirms_inverses_and_flags_lossy_pairs() {
+        let sub = SubCommand::parse(&[
+            "forward:base64-encode".to_string(),
+            "inverse:base64-decode".to_string(),
+        ])
+        .unwrap();
+        let out = transmute(Command::Verify, &sub, "round and round".to_string()).unwrap();
+        assert!(out.starts_with("round-trip ok"), "got {out}");
+
+        // lowercase loses information; uppercase cannot undo it.
+        let sub = SubCommand::parse(&[
+            "forward:lowercase".to_string(),
+            "inverse:uppercase".to_string(),
+        ])
+        .unwrap();
+        let err = transmute(Command::Verify, &sub, "Mixed Case".to_string()).unwrap_err();
+        assert!(err.to_string().contains("mismatch"), "got {err}");
+    }
+
+    #[test]
+    fn fit_splits_long_text_into_numbered_chunks_within_the_limit() {
+        let sub = SubCommand::parse(&["limit:50".to_string()]).unwrap();
+        let input = "the quick brown fox jumps over the lazy dog and keeps on running \
+                     far beyond the river bend"
+            .to_string();
+        let out = transmute(Command::Fit, &sub, input).unwrap();
+
+        let chunks: Vec<&str> = out.lines().collect();
+        let total = chunks.len();
+        assert!(total > 1);
+        for (i, chunk) in chunks.iter().enumerate() {
+            assert!(
+                chunk.graphemes(true).count() <= 50,
+                "chunk {i} too long: {chunk:?}"
+            );
+            assert!(chunk.ends_with(&format!("({}/{total})", i + 1)), "got {chunk:?}");
+        }
+
+        // Short input passes through without a marker.
+        let out = transmute(Command::Fit, &sub, "short".to_string()).unwrap();
+        assert_eq!(out, "short");
+    }
+
+    #[test]
+    fn rtrim_strips_trailing_but_not_leading_whitespace() {
+        let input = "  indented  \ncode\t\nclean".to_string();
+        let out = transmute(Command::Rtrim, &no_args(), input).unwrap();
+        assert_eq!(out, "  indented\ncode\nclean");
+    }
+
+    #[test]
+    fn final_newline_is_added_or_collapsed_to_exactly_one() {
+        let out = transmute(Command::EnsureFinalNewline, &no_args(), "no newline".to_string())
+            .unwrap();
+        assert_eq!(out, "no newline\n");
+
+        let out = transmute(Command::EnsureFinalNewline, &no_args(), "too many\n\n\n".to_string())
+            .unwrap();
+        assert_eq!(out, "too many\n");
+    }
+
+    #[test]
+    fn head_and_tail_keep_the_requested_lines() {
+        let input = "one\ntwo\nthree\nfour\nfive";
+        let sub = SubCommand::parse(&["n:2".to_string()]).unwrap();
+
+        let out = transmute(Command::Head, &sub, input.to_string()).unwrap();
+        assert_eq!(out, "one\ntwo");
+
+        let out = transmute(Command::Tail, &sub, input.to_string()).unwrap();
+        assert_eq!(out, "four\nfive");
+    }
+
+    #[test]
+    fn quotes_convert_between_smart_and_dumb() {
+        let smart = "\u{201C}Hello,\u{201D} she said \u{2014} \u{2018}wait\u{2026}\u{2019}";
+        let dumb = "\"Hello,\" she said -- 'wait...'";
+
+        let out = transmute(Command::DumbQuotes, &no_args(), smart.to_string()).unwrap();
+        assert_eq!(out, dumb);
+
+        let out = transmute(Command::SmartQuotes, &no_args(), dumb.to_string()).unwrap();
+        assert_eq!(out, smart);
+
+        // An apostrophe mid-word picks the closing glyph.
+        let out = transmute(Command::SmartQuotes, &no_args(), "it's".to_string()).unwrap();
+        assert_eq!(out, "it\u{2019}s");
+    }
+
+    #[test]
+    fn when_transforms_only_matching_lines() {
+        let sub = SubCommand::parse(&["match:crab".to_string(), "then:uppercase".to_string()])
+            .unwrap();
+        let input = "one crab here\nno match\ntwo crabs there".to_string();
+        let out = transmute(Command::When, &sub, input).unwrap();
+        assert_eq!(out, "ONE CRAB HERE\nno match\nTWO CRABS THERE");
+
+        let sub = SubCommand::parse(&["match:crab".to_string()]).unwrap();
+        assert!(transmute(Command::When, &sub, String::new()).is_err());
+    }
+
+    #[test]
+    fn tokens_sorts_and_uniques_a_word_bag() {
+        let sub = SubCommand::parse(&["sort:true".to_string(), "unique:true".to_string()])
+            .unwrap();
+        let out = transmute(Command::Tokens, &sub, "pear apple pear\nbanana apple".to_string())
+            .unwrap();
+        assert_eq!(out, "apple banana pear");
+
+        let sub = SubCommand::parse(&["unique:true".to_string(), "lines:true".to_string()])
+            .unwrap();
+        let out = transmute(Command::Tokens, &sub, "b a b".to_string()).unwrap();
+        assert_eq!(out, "b\na");
+    }
+
+    #[test]
+    fn ruler_matches_the_widest_line() {
+        let input = "short\na longer line here".to_string();
+        let widest = "a longer line here".chars().count();
+        let out = transmute(Command::Ruler, &no_args(), input.clone()).unwrap();
+
+        let mut lines = out.lines();
+        let tens = lines.next().unwrap();
+        let units = lines.next().unwrap();
+        assert_eq!(tens.chars().count(), widest);
+        assert_eq!(units.chars().count(), widest);
+        assert!(units.starts_with("1234567890"));
+        assert_eq!(tens.chars().nth(9), Some('1'));
+        assert_eq!(lines.collect::<Vec<&str>>().join("\n"), input);
+    }
+
+    #[test]
+    fn near_miss_typo_gets_a_did_you_mean_suggestion() {
+        let err = "lowecase".parse::<Command>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid command: lowecase. Did you mean 'lowercase'?"
+        );
+
+        // Nothing plausible nearby: no suggestion.
+        let err = "frobnicate".parse::<Command>().unwrap_err();
+        assert_eq!(err.to_string(), "Invalid command: frobnicate");
+    }
+
+    #[test]
+    fn prose_stats_counts_paragraphs_and_averages_sentences() {
+        let input = "One two three. Four five?\n\nSix seven eight nine ten!".to_string();
+        let out = transmute(Command::ProseStats, &no_args(), input).unwrap();
+        assert_eq!(
+            out,
+            "paragraphs: 2  sentences: 3  avg sentence: 3.3 words  longest: 5 words"
+        );
+
+        let out = transmute(Command::ProseStats, &no_args(), String::new()).unwrap();
+        assert!(out.starts_with("paragraphs: 0  sentences: 0"), "got {out}");
+    }
+
+    #[test]
+    fn slugify_case_keep_preserves_letter_case() {
+        let out = transmute(Command::Slugify, &no_args(), "Hello World".to_string()).unwrap();
+        assert_eq!(out, "hello-world");
+
+        let sub = SubCommand::parse(&["case:keep".to_string()]).unwrap();
+        let out = transmute(Command::Slugify, &sub, "Hello World".to_string()).unwrap();
+        assert_eq!(out, "Hello-World");
+
+        let out = transmute(Command::Slugify, &sub, "  Crème! Brûlée  ".to_string()).unwrap();
+        assert_eq!(out, "Creme-Brulee");
+    }
+
+    #[test]
+    fn affix_prefixes_every_line() {
+        let sub = SubCommand::parse(&["prefix:// ".to_string()]).unwrap();
+        let out = transmute(Command::Affix, &sub, "one\ntwo\nthree".to_string()).unwrap();
+        assert_eq!(out, "// one\n// two\n// three");
+    }
+
+    #[test]
+    fn affix_blank_skip_leaves_empty_lines_alone() {
+        let sub = SubCommand::parse(&["suffix:;".to_string(), "blank:skip".to_string()]).unwrap();
+        let out = transmute(Command::Affix, &sub, "a\n\nb".to_string()).unwrap();
+        assert_eq!(out, "a;\n\nb;");
+    }
+
+    #[test]
+    fn align_pads_keys_into_a_column() {
+        let input = "host=localhost\nport=8080\nretries=3".to_string();
+        let out = transmute(Command::Align, &no_args(), input).unwrap();
+        assert_eq!(out, "host    = localhost\nport    = 8080\nretries = 3");
+    }
+
+    #[test]
+    fn align_honors_sep_and_passes_plain_lines_through() {
+        let sub = SubCommand::parse(&["sep::".to_string()]).unwrap();
+        let input = "# comment\nname: Ada\nrole: engineer".to_string();
+        let out = transmute(Command::Align, &sub, input).unwrap();
+        assert_eq!(out, "# comment\nname : Ada\nrole : engineer");
+    }
+
+    #[test]
+    fn mask_keeps_the_last_four_by_default() {
+        let out = transmute(Command::Mask, &no_args(), "4111111111111111".to_string()).unwrap();
+        assert_eq!(out, "************1111");
+    }
+
+    #[test]
+    fn mask_is_grapheme_aware_and_configurable() {
+        let sub = SubCommand::parse(&["keep:2".to_string(), "char:#".to_string()]).unwrap();
+        let out = transmute(Command::Mask, &sub, "née🦀é".to_string()).unwrap();
+        assert_eq!(out, "###🦀é");
+
+        let short = transmute(Command::Mask, &no_args(), "abc".to_string()).unwrap();
+        assert_eq!(short, "abc");
+    }
+
+    #[test]
+    fn filter_chars_keeps_only_alphanumerics() {
+        let sub = SubCommand::parse(&["keep:alpha,digit".to_string()]).unwrap();
+        let out = transmute(Command::FilterChars, &sub, "a1! b-2é".to_string()).unwrap();
+        assert_eq!(out, "a1b2é");
+    }
+
+    #[test]
+    fn filter_chars_strips_punctuation() {
+        let sub = SubCommand::parse(&["strip:punct".to_string()]).unwrap();
+        let out = transmute(Command::FilterChars, &sub, "hi, there!".to_string()).unwrap();
+        assert_eq!(out, "hi there");
+
+        let both = SubCommand::parse(&["keep:alpha".to_string(), "strip:punct".to_string()])
+            .unwrap();
+        assert!(transmute(Command::FilterChars, &both, "x".to_string()).is_err());
+        let bad = SubCommand::parse(&["keep:emoji".to_string()]).unwrap();
+        assert!(transmute(Command::FilterChars, &bad, "x".to_string()).is_err());
+    }
+
+    #[test]
+    fn comment_box_wraps_two_lines_in_c_style() {
+        let out = transmute(Command::CommentBox, &no_args(), "hi\nworld".to_string()).unwrap();
+        assert_eq!(out, "/*********\n * hi    *\n * world *\n ********/");
+    }
+
+    #[test]
+    fn comment_box_lang_picks_the_comment_style() {
+        let sub = SubCommand::parse(&["lang:py".to_string()]).unwrap();
+        let out = transmute(Command::CommentBox, &sub, "hi".to_string()).unwrap();
+        assert_eq!(out, "# ******\n# * hi *\n# ******");
+
+        let sub = SubCommand::parse(&["lang:go".to_string()]).unwrap();
+        assert!(transmute(Command::CommentBox, &sub, "hi".to_string()).is_err());
+    }
+
+    #[test]
+    fn html_escape_round_trips_all_five_specials() {
+        let input = r#"<a href="x">Tom & Jerry's</a>"#.to_string();
+        let escaped = transmute(Command::HtmlEscape, &no_args(), input.clone()).unwrap();
+        assert_eq!(
+            escaped,
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&#39;s&lt;/a&gt;"
+        );
+        let back = transmute(Command::HtmlUnescape, &no_args(), escaped).unwrap();
+        assert_eq!(back, input);
+    }
+
+    #[test]
+    fn html_unescape_handles_numeric_forms_and_bare_ampersand() {
+        let out = transmute(
+            Command::HtmlUnescape,
+            &no_args(),
+            "&amp; &#38; &#x26; &#X26; fish & chips &bogus;".to_string(),
+        )
+        .unwrap();
+        assert_eq!(out, "& & & & fish & chips &bogus;");
+    }
+
+    #[test]
+    fn command_converts_to_and_from_str() {
+        let command = Command::try_from("sort-lines").unwrap();
+        assert_eq!(command, Command::SortLines);
+        assert_eq!(command.as_ref(), "sort-lines");
+        assert_eq!(command.to_string(), "sort-lines");
+
+        assert!(matches!(
+            Command::try_from("florbify"),
+            Err(TransformError::InvalidCommand(_))
+        ));
+    }
+
+    #[test]
+    fn unknown_command_errors() {
+        assert!(matches!(
+            Command::from_str("florbify"),
+            Err(TransformError::InvalidCommand(_))
+        ));
+    }
+
+    #[test]
+    fn subcommand_rejects_bare_tokens() {
+        assert!(SubCommand::parse(&["notapair".to_string()]).is_err());
+    }
+}
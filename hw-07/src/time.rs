@@ -0,0 +1,81 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+
+use crate::text_utils::{SubCommand, TransformError};
+
+/// Converts time values found in the input, for log processing:
+/// `from:unix to:iso` turns epoch timestamps into ISO-8601, `from:iso
+/// to:unix` goes the other way. Lines are tokenized on whitespace;
+/// tokens that parse as the source format are converted, everything
+/// else passes through unchanged. Unix input may be in seconds or
+/// milliseconds — values too large for a plausible seconds timestamp
+/// are read as millis.
+pub fn timestamp(sub: &SubCommand, input: &str) -> Result<String, TransformError> {
+    let from = sub.get("from").unwrap_or("unix");
+    let to = sub.get("to").unwrap_or("iso");
+
+    let convert: fn(&str) -> Option<String> = match (from, to) {
+        ("unix", "iso") => unix_to_iso,
+        ("iso", "unix") => iso_to_unix,
+        _ => {
+            return Err(TransformError::InvalidArguments(format!(
+                "unsupported conversion from:{from} to:{to}; use from:unix to:iso or from:iso to:unix"
+            )))
+        }
+    };
+
+    let lines: Vec<String> = input
+        .lines()
+        .map(|line| {
+            line.split_whitespace()
+                .map(|token| convert(token).unwrap_or_else(|| token.to_string()))
+                .collect::<Vec<String>>()
+                .join(" ")
+        })
+        .collect();
+    Ok(lines.join("\n"))
+}
+
+/// Epoch values at or above this are taken as milliseconds: it is the
+/// year 5138 in seconds but 2001 in millis.
+const MILLIS_CUTOFF: i64 = 100_000_000_000;
+
+fn unix_to_iso(token: &str) -> Option<String> {
+    let value: i64 = token.parse().ok()?;
+    let time = if value.abs() >= MILLIS_CUTOFF {
+        DateTime::<Utc>::from_timestamp_millis(value)?
+    } else {
+        DateTime::<Utc>::from_timestamp(value, 0)?
+    };
+    Some(time.to_rfc3339_opts(SecondsFormat::Secs, true))
+}
+
+fn iso_to_unix(token: &str) -> Option<String> {
+    let time = DateTime::parse_from_rfc3339(token).ok()?;
+    Some(time.timestamp().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unix_seconds_become_iso_and_back() {
+        let sub = SubCommand::default();
+        let out = timestamp(&sub, "boot at 1700000000 ok").unwrap();
+        assert_eq!(out, "boot at 2023-11-14T22:13:20Z ok");
+
+        let sub = SubCommand::parse(&["from:iso".to_string(), "to:unix".to_string()]).unwrap();
+        let back = timestamp(&sub, &out).unwrap();
+        assert_eq!(back, "boot at 1700000000 ok");
+    }
+
+    #[test]
+    fn millis_are_detected_and_junk_passes_through() {
+        let sub = SubCommand::default();
+        let out = timestamp(&sub, "1700000000000 then 12:34 later").unwrap();
+        assert_eq!(out, "2023-11-14T22:13:20Z then 12:34 later");
+
+        let sub = SubCommand::parse(&["to:klingon".to_string()]).unwrap();
+        assert!(timestamp(&sub, "1700000000").is_err());
+    }
+}
@@ -0,0 +1,78 @@
+use std::path::Path;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use notify::Watcher as _;
+
+use crate::input;
+use crate::text_utils::{Command, Registry, TransformError};
+
+/// How long to wait after an event before re-rendering. Editors emit
+/// several events per save (write, truncate, metadata); one render per
+/// save is what the user expects.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Live-preview mode: re-runs the transform on the watched file every
+/// time it changes, clearing the screen first. Runs until the watcher
+/// drops (or Ctrl-C).
+pub fn run_watch(
+    registry: &Registry,
+    command: Command,
+    options: &input::CliOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let path = options.watch.as_deref().expect("watch mode requires a path");
+
+    let (tx, rx) = mpsc::channel::<()>();
+    let mut watcher =
+        notify::recommended_watcher(move |event: Result<notify::Event, notify::Error>| {
+            if event.is_ok() {
+                let _ = tx.send(());
+            }
+        })?;
+    watcher.watch(path, notify::RecursiveMode::NonRecursive)?;
+
+    render(registry, command, path, options);
+    while await_change(&rx, DEBOUNCE).is_ok() {
+        render(registry, command, path, options);
+    }
+    Ok(())
+}
+
+/// Blocks until the file changes, then swallows the rest of the burst so
+/// rapid successive events collapse into a single re-render. Errors only
+/// when the watcher side of the channel is gone.
+fn await_change(rx: &mpsc::Receiver<()>, debounce: Duration) -> Result<(), mpsc::RecvError> {
+    rx.recv()?;
+    while rx.recv_timeout(debounce).is_ok() {}
+    Ok(())
+}
+
+fn render(registry: &Registry, command: Command, path: &Path, options: &input::CliOptions) {
+    // Clear the screen and home the cursor before fresh output.
+    print!("\x1b[2J\x1b[H");
+    let result = std::fs::read_to_string(path)
+        .map_err(TransformError::from)
+        .and_then(|text| crate::execute_command(registry, command, &options.sub, text, options));
+    if let Err(e) = result {
+        eprintln!("Error: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_burst_of_events_collapses_into_one_run() {
+        let (tx, rx) = mpsc::channel();
+        for _ in 0..5 {
+            tx.send(()).unwrap();
+        }
+
+        await_change(&rx, Duration::from_millis(10)).unwrap();
+        assert!(rx.try_recv().is_err(), "burst not fully drained");
+
+        drop(tx);
+        assert!(await_change(&rx, Duration::from_millis(10)).is_err());
+    }
+}
@@ -0,0 +1,125 @@
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, Read};
+use std::net::TcpStream;
+use std::path::Path;
+
+use hw_09::{send_message, Message};
+
+fn main() {
+    let addr = env::args()
+        .nth(1)
+        .unwrap_or_else(|| "127.0.0.1:11111".to_string());
+
+    let mut stream = match TcpStream::connect(&addr) {
+        Ok(stream) => stream,
+        Err(e) => {
+            eprintln!("Failed to connect to {addr}: {e}");
+            std::process::exit(1);
+        }
+    };
+    println!(
+        "Connected to {addr}. Commands: .file <path>, .image <path>, .stdin <name>, .quit"
+    );
+
+    let stdin = io::stdin();
+    let mut stdin = stdin.lock();
+    loop {
+        let mut line = String::new();
+        match stdin.read_line(&mut line) {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(e) => {
+                eprintln!("Failed to read line: {e}");
+                break;
+            }
+        }
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        // `.stdin` consumes the rest of the input as the file body, so it
+        // has to be handled here where the reader is available.
+        if let Some(name) = trimmed.strip_prefix(".stdin ") {
+            match read_stdin_file(name, &mut stdin) {
+                Ok(message) => {
+                    if let Err(e) = send_message(&mut stream, &message) {
+                        eprintln!("Failed to send message: {e}");
+                    }
+                }
+                Err(e) => eprintln!("Error: {e}"),
+            }
+            break;
+        }
+
+        let message = match parse_line(trimmed) {
+            Ok(Some(message)) => message,
+            Ok(None) => break,
+            Err(e) => {
+                eprintln!("Error: {e}");
+                continue;
+            }
+        };
+
+        if let Err(e) = send_message(&mut stream, &message) {
+            eprintln!("Failed to send message: {e}");
+            break;
+        }
+    }
+}
+
+/// Wraps everything left in `reader` into a file upload named `name`.
+/// Meant for piped bulk input: `cat data | client <addr> .stdin data.bin`
+/// sends the piped bytes without touching the disk.
+fn read_stdin_file(name: &str, reader: &mut impl Read) -> io::Result<Message> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+    Ok(Message::File {
+        name: name.to_string(),
+        data,
+    })
+}
+
+/// Turns an input line into a message; `Ok(None)` means `.quit`.
+fn parse_line(line: &str) -> io::Result<Option<Message>> {
+    if line == ".quit" {
+        return Ok(None);
+    }
+    if let Some(path) = line.strip_prefix(".file ") {
+        let name = file_name(path)?;
+        let data = fs::read(path)?;
+        return Ok(Some(Message::File { name, data }));
+    }
+    if let Some(path) = line.strip_prefix(".image ") {
+        let data = fs::read(path)?;
+        return Ok(Some(Message::Image(data)));
+    }
+    Ok(Some(Message::Text(line.to_string())))
+}
+
+fn file_name(path: &str) -> io::Result<String> {
+    Path::new(path)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .map(str::to_string)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "invalid file path"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn piped_bytes_become_a_named_file_message() {
+        let mut piped = io::Cursor::new(b"\x00binary\xffpayload".to_vec());
+        let message = read_stdin_file("data.bin", &mut piped).unwrap();
+        match message {
+            Message::File { name, data } => {
+                assert_eq!(name, "data.bin");
+                assert_eq!(data, b"\x00binary\xffpayload");
+            }
+            other => panic!("expected a file message, got {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use std::env;
+use std::fs;
+use std::io::ErrorKind;
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::thread;
+
+use hw_09::{receive_message, Message};
+
+const FILE_STORE: &str = "files";
+const IMAGE_STORE: &str = "images";
+
+fn main() {
+    let addr = env::args()
+        .nth(1)
+        .unwrap_or_else(|| "127.0.0.1:11111".to_string());
+
+    let listener = match TcpListener::bind(&addr) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("Failed to bind {addr}: {e}");
+            std::process::exit(1);
+        }
+    };
+    println!("Listening on {addr}");
+
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                thread::spawn(move || handle_client(stream));
+            }
+            Err(e) => eprintln!("Failed to accept connection: {e}"),
+        }
+    }
+}
+
+fn handle_client(mut stream: TcpStream) {
+    let peer = stream
+        .peer_addr()
+        .map(|a| a.to_string())
+        .unwrap_or_else(|_| "unknown".to_string());
+    println!("Client connected: {peer}");
+
+    loop {
+        match receive_message(&mut stream) {
+            Ok(message) => {
+                if let Err(e) = process_message(message) {
+                    eprintln!("Failed to process message from {peer}: {e}");
+                }
+            }
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => {
+                println!("Client disconnected: {peer}");
+                break;
+            }
+            Err(e) => {
+                eprintln!("Failed to receive message from {peer}: {e}");
+                break;
+            }
+        }
+    }
+}
+
+fn process_message(message: Message) -> std::io::Result<()> {
+    match message {
+        Message::Text(text) => println!("Text: {text}"),
+        Message::File { name, data } => {
+            let path = save_file(FILE_STORE, &name, &data)?;
+            println!("Saved file to {}", path.display());
+        }
+        Message::Image(data) => {
+            let path = save_image(&data)?;
+            println!("Saved image to {}", path.display());
+        }
+    }
+    Ok(())
+}
+
+fn save_file(dir: &str, name: &str, data: &[u8]) -> std::io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let path = PathBuf::from(dir).join(timestamped(name));
+    fs::write(&path, data)?;
+    Ok(path)
+}
+
+/// Images are re-encoded to PNG regardless of their original format.
+fn save_image(data: &[u8]) -> std::io::Result<PathBuf> {
+    fs::create_dir_all(IMAGE_STORE)?;
+    let image = image::load_from_memory(data)
+        .map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e))?;
+    let path = PathBuf::from(IMAGE_STORE).join(timestamped("image.png"));
+    image
+        .save(&path)
+        .map_err(std::io::Error::other)?;
+    Ok(path)
+}
+
+fn timestamped(name: &str) -> String {
+    format!("{}_{}", chrono::Utc::now().timestamp(), name)
+}
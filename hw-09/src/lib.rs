@@ -0,0 +1,33 @@
+use std::io::{self, Read, Write};
+
+use serde::{Deserialize, Serialize};
+
+/// Everything the client and server exchange over the wire.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Message {
+    Text(String),
+    Image(Vec<u8>),
+    File { name: String, data: Vec<u8> },
+}
+
+/// Writes a message as a length-prefixed CBOR frame.
+pub fn send_message<W: Write>(writer: &mut W, message: &Message) -> io::Result<()> {
+    let payload = serde_cbor::to_vec(message)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let len = payload.len() as u32;
+    writer.write_all(&len.to_be_bytes())?;
+    writer.write_all(&payload)?;
+    writer.flush()
+}
+
+/// Reads one length-prefixed CBOR frame and decodes it.
+pub fn receive_message<R: Read>(reader: &mut R) -> io::Result<Message> {
+    let mut len_bytes = [0u8; 4];
+    reader.read_exact(&mut len_bytes)?;
+    let len = u32::from_be_bytes(len_bytes) as usize;
+
+    let mut payload = vec![0u8; len];
+    reader.read_exact(&mut payload)?;
+
+    serde_cbor::from_slice(&payload).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
@@ -0,0 +1,280 @@
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use tokio::io::{AsyncBufReadExt, AsyncWrite, BufReader};
+use tokio::net::tcp::OwnedWriteHalf;
+use tokio::net::TcpStream;
+use tracing::{error, info};
+
+use common::{receive_message, send_message, Message, PROTOCOL_VERSION};
+
+#[derive(Debug, Parser)]
+#[command(about = "Chat client: sends text, files, and images to the server")]
+struct Args {
+    #[arg(long, default_value = "127.0.0.1")]
+    host: String,
+    #[arg(long, default_value_t = 11111)]
+    port: u16,
+    /// How many times to retry connecting before giving up.
+    #[arg(long, default_value_t = 0)]
+    retry: u32,
+    /// Overall deadline in seconds for sending one message.
+    #[arg(long, default_value_t = 30)]
+    timeout: u64,
+    /// Token to authenticate with, for servers that require one.
+    #[arg(long)]
+    token: Option<String>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
+    let args = Args::parse();
+    let addr = format!("{}:{}", args.host, args.port);
+
+    let mut write_half = establish(&addr, args.retry, args.token.as_deref()).await?;
+    let deadline = Duration::from_secs(args.timeout);
+
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    while let Some(line) = lines.next_line().await? {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        let messages = match parse_line(trimmed).await {
+            Ok(Some(messages)) => messages,
+            Ok(None) => break,
+            Err(e) => {
+                error!("{e:#}");
+                continue;
+            }
+        };
+
+        for message in &messages {
+            if let Err(e) = send_with_deadline(&mut write_half, message, deadline).await {
+                error!("Failed to send message: {e}");
+                if args.retry == 0 {
+                    return Ok(());
+                }
+                // A stuck or dead connection is unusable; start over on a new one.
+                write_half = establish(&addr, args.retry, args.token.as_deref()).await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Connects, splits the stream, and spawns the task that prints messages
+/// pushed by the server. Returns the write half for the input loop.
+async fn establish(addr: &str, retries: u32, token: Option<&str>) -> Result<OwnedWriteHalf> {
+    let stream = connect_with_retry(addr, retries).await?;
+    info!("Connected to {addr}. Commands: .file <path>, .image <path>, .paste <text>, .get <id>, .quit");
+
+    let (mut read_half, mut write_half) = stream.into_split();
+
+    // Version handshake before anything else goes over the wire.
+    send_message(
+        &mut write_half,
+        &Message::Hello {
+            version: PROTOCOL_VERSION,
+        },
+    )
+    .await
+    .context("failed to send Hello")?;
+    match receive_message(&mut read_half)
+        .await
+        .context("handshake failed")?
+    {
+        Message::Welcome { version } => info!("Server speaks protocol version {version}"),
+        Message::Error(e) => anyhow::bail!("server rejected handshake: {e}"),
+        other => anyhow::bail!("unexpected handshake reply: {other:?}"),
+    }
+
+    // Servers started with --token expect it as the first message after
+    // the handshake.
+    if let Some(token) = token {
+        send_message(&mut write_half, &Message::Auth(token.to_string()))
+            .await
+            .context("failed to send auth token")?;
+    }
+
+    // Server pushes relayed text messages at any time; print them as they come.
+    tokio::spawn(async move {
+        let mut reader = read_half;
+        loop {
+            match receive_message(&mut reader).await {
+                Ok(Message::Text(text)) => println!("{text}"),
+                Ok(Message::PasteStored(id)) => println!("paste stored as {id}"),
+                Ok(Message::Error(e)) => error!("Server error: {e}"),
+                Ok(other) => info!("Unexpected message from server: {other:?}"),
+                Err(e) => {
+                    error!("Lost connection to server: {e}");
+                    break;
+                }
+            }
+        }
+    });
+
+    Ok(write_half)
+}
+
+/// Sends one message with an overall deadline so a server that stops
+/// reading cannot hang the input loop forever.
+async fn send_with_deadline<W>(
+    writer: &mut W,
+    message: &Message,
+    deadline: Duration,
+) -> std::io::Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    match tokio::time::timeout(deadline, send_message(writer, message)).await {
+        Ok(result) => result,
+        Err(_) => Err(std::io::Error::new(
+            std::io::ErrorKind::TimedOut,
+            format!("send did not finish within {deadline:?}"),
+        )),
+    }
+}
+
+async fn connect_with_retry(addr: &str, retries: u32) -> Result<TcpStream> {
+    let mut attempt = 0;
+    loop {
+        match TcpStream::connect(addr).await {
+            Ok(stream) => return Ok(stream),
+            Err(e) if attempt < retries => {
+                attempt += 1;
+                error!("Connection attempt {attempt} failed: {e}, retrying...");
+                tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+            }
+            Err(e) => {
+                return Err(e).with_context(|| format!("failed to connect to {addr}"));
+            }
+        }
+    }
+}
+
+/// Files bigger than this are split into `Message::FilePart` chunks so
+/// one flaky frame doesn't lose the whole transfer.
+const FILE_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Turns an input line into the messages to send; `Ok(None)` means `.quit`.
+async fn parse_line(line: &str) -> Result<Option<Vec<Message>>> {
+    if line == ".quit" {
+        return Ok(None);
+    }
+    if let Some(path) = line.strip_prefix(".file ") {
+        let name = file_name(path)?;
+        let data = tokio::fs::read(path)
+            .await
+            .with_context(|| format!("failed to read {path}"))?;
+        return Ok(Some(chunk_file(name, data, FILE_CHUNK_SIZE)));
+    }
+    if let Some(text) = line.strip_prefix(".paste ") {
+        return Ok(Some(vec![Message::Paste(text.to_string())]));
+    }
+    if let Some(id) = line.strip_prefix(".get ") {
+        return Ok(Some(vec![Message::PasteGet(id.trim().to_string())]));
+    }
+    if let Some(path) = line.strip_prefix(".image ") {
+        let data = tokio::fs::read(path)
+            .await
+            .with_context(|| format!("failed to read {path}"))?;
+        return Ok(Some(vec![Message::Image(data)]));
+    }
+    Ok(Some(vec![Message::Text(line.to_string())]))
+}
+
+/// Small files stay a single `Message::File`; anything larger becomes a
+/// sequence of numbered `Message::FilePart`s.
+fn chunk_file(name: String, data: Vec<u8>, chunk_size: usize) -> Vec<Message> {
+    if data.len() <= chunk_size {
+        return vec![Message::File { name, data }];
+    }
+
+    let total = data.len().div_ceil(chunk_size) as u32;
+    data.chunks(chunk_size)
+        .enumerate()
+        .map(|(index, chunk)| Message::FilePart {
+            name: name.clone(),
+            index: index as u32,
+            total,
+            data: chunk.to_vec(),
+        })
+        .collect()
+}
+
+fn file_name(path: &str) -> Result<String> {
+    Path::new(path)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .map(str::to_string)
+        .context("invalid file path")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn send_against_non_reading_sink_times_out() {
+        // A tiny duplex buffer with no reader: the write fills it and stalls.
+        let (mut writer, _reader) = tokio::io::duplex(16);
+        let message = Message::Text("x".repeat(1024));
+
+        let err = send_with_deadline(&mut writer, &message, Duration::from_millis(50))
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+    }
+
+    #[test]
+    fn large_file_is_chunked_into_numbered_parts() {
+        let data: Vec<u8> = (0..10u8).collect();
+        let messages = chunk_file("big.bin".to_string(), data.clone(), 4);
+        assert_eq!(messages.len(), 3);
+
+        let mut reassembled = Vec::new();
+        for (i, message) in messages.iter().enumerate() {
+            match message {
+                Message::FilePart {
+                    name,
+                    index,
+                    total,
+                    data,
+                } => {
+                    assert_eq!(name, "big.bin");
+                    assert_eq!(*index, i as u32);
+                    assert_eq!(*total, 3);
+                    reassembled.extend_from_slice(data);
+                }
+                other => panic!("expected FilePart, got {other:?}"),
+            }
+        }
+        assert_eq!(reassembled, data);
+    }
+
+    #[test]
+    fn small_file_stays_a_single_message() {
+        let messages = chunk_file("small.txt".to_string(), vec![1, 2, 3], 4);
+        assert!(matches!(messages.as_slice(), [Message::File { .. }]));
+    }
+
+    #[tokio::test]
+    async fn send_within_deadline_succeeds() {
+        let (mut writer, mut reader) = tokio::io::duplex(4096);
+        let message = Message::Text("hello".to_string());
+
+        send_with_deadline(&mut writer, &message, Duration::from_secs(1))
+            .await
+            .unwrap();
+        drop(writer);
+
+        let echoed = receive_message(&mut reader).await.unwrap();
+        assert!(matches!(echoed, Message::Text(t) if t == "hello"));
+    }
+}
@@ -0,0 +1,117 @@
+use std::io;
+
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Version of the wire protocol. Bump whenever [`Message`] changes
+/// incompatibly; the handshake rejects clients on a different version.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Everything the client and server exchange over the wire.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Message {
+    /// First message from a client: announces its protocol version.
+    Hello { version: u32 },
+    /// Server's reply to a compatible [`Message::Hello`].
+    Welcome { version: u32 },
+    /// Shared-secret token, sent right after the handshake when the
+    /// server requires one.
+    Auth(String),
+    Text(String),
+    Image(Vec<u8>),
+    File { name: String, data: Vec<u8> },
+    /// One fixed-size piece of a large file; the server reassembles all
+    /// `total` parts (in any order) back into the original bytes.
+    FilePart {
+        name: String,
+        index: u32,
+        total: u32,
+        data: Vec<u8>,
+    },
+    /// Pastebin upload: the server stores the text and replies with a
+    /// [`Message::PasteStored`] carrying its ID.
+    Paste(String),
+    /// Server's reply to [`Message::Paste`]: the ID to retrieve it by.
+    PasteStored(String),
+    /// Retrieves a stored paste by ID; answered with [`Message::Text`]
+    /// or [`Message::Error`] for an unknown ID.
+    PasteGet(String),
+    Error(String),
+}
+
+/// Writes a message as a length-prefixed CBOR frame.
+pub async fn send_message<W>(writer: &mut W, message: &Message) -> io::Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    let payload = serde_cbor::to_vec(message)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let len = payload.len() as u32;
+    writer.write_all(&len.to_be_bytes()).await?;
+    writer.write_all(&payload).await?;
+    writer.flush().await
+}
+
+/// Reads one length-prefixed frame without decoding it, so callers can
+/// report the raw bytes when decoding fails.
+pub async fn receive_frame<R>(reader: &mut R) -> io::Result<Vec<u8>>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut len_bytes = [0u8; 4];
+    reader.read_exact(&mut len_bytes).await?;
+    let len = u32::from_be_bytes(len_bytes) as usize;
+
+    let mut payload = vec![0u8; len];
+    reader.read_exact(&mut payload).await?;
+    Ok(payload)
+}
+
+/// Reads one frame and decodes it into a [`Message`].
+pub async fn receive_message<R>(reader: &mut R) -> io::Result<Message>
+where
+    R: AsyncRead + Unpin,
+{
+    let payload = receive_frame(reader).await?;
+    decode_message(&payload)
+}
+
+pub fn decode_message(payload: &[u8]) -> io::Result<Message> {
+    serde_cbor::from_slice(payload).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn message_round_trips_through_a_frame() {
+        let mut buffer = Vec::new();
+        let message = Message::File {
+            name: "notes.txt".to_string(),
+            data: b"hello".to_vec(),
+        };
+        send_message(&mut buffer, &message).await.unwrap();
+
+        let decoded = receive_message(&mut buffer.as_slice()).await.unwrap();
+        match decoded {
+            Message::File { name, data } => {
+                assert_eq!(name, "notes.txt");
+                assert_eq!(data, b"hello");
+            }
+            other => panic!("unexpected message: {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn truncated_frame_is_an_eof_error() {
+        let mut buffer = Vec::new();
+        send_message(&mut buffer, &Message::Text("hi".to_string()))
+            .await
+            .unwrap();
+        buffer.truncate(buffer.len() - 1);
+
+        let err = receive_message(&mut buffer.as_slice()).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+}
@@ -0,0 +1,838 @@
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use futures_util::SinkExt;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast;
+use tokio::task::JoinSet;
+use tracing::{error, info, warn};
+
+use common::{
+    decode_message, receive_frame, receive_message, send_message, Message, PROTOCOL_VERSION,
+};
+
+pub const FILE_STORE: &str = "files";
+pub const IMAGE_STORE: &str = "images";
+
+/// How many relayed messages a slow client may lag behind.
+const BROADCAST_CAPACITY: usize = 64;
+
+/// How long shutdown waits for in-flight connections to finish before
+/// aborting them. Keeps Ctrl-C from truncating a file mid-write.
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(10);
+
+/// How often `follow_file` checks for appended data.
+const FOLLOW_POLL: Duration = Duration::from_millis(100);
+
+/// How many bytes of an undecodable frame make it into the error log.
+const RAW_DUMP_LIMIT: usize = 64;
+
+/// Running totals, shared across all connection handlers.
+#[derive(Debug, Default)]
+pub struct Counters {
+    pub connections: AtomicU64,
+    pub text_messages: AtomicU64,
+    pub files_saved: AtomicU64,
+    pub images_saved: AtomicU64,
+    pub bytes_received: AtomicU64,
+}
+
+/// Partially received chunked upload, keyed by filename in
+/// [`ServerState::pending_parts`].
+#[derive(Debug, Default)]
+struct FileAssembly {
+    total: u32,
+    parts: HashMap<u32, Vec<u8>>,
+}
+
+/// Optional moderation rules applied to incoming text messages before
+/// they are logged or relayed.
+#[derive(Debug, Default)]
+pub struct TextPolicy {
+    /// Reject messages longer than this many chars.
+    pub max_text_len: Option<usize>,
+    /// Reject messages matching any of these patterns.
+    pub blocklist: Option<regex::RegexSet>,
+}
+
+impl TextPolicy {
+    /// Why `text` is rejected, or `None` if it passes.
+    fn violation(&self, text: &str) -> Option<String> {
+        if let Some(max) = self.max_text_len {
+            let len = text.chars().count();
+            if len > max {
+                return Some(format!("message is {len} chars, limit is {max}"));
+            }
+        }
+        if let Some(blocklist) = &self.blocklist {
+            if blocklist.is_match(text) {
+                return Some("message matches the blocklist".to_string());
+            }
+        }
+        None
+    }
+}
+
+pub struct ServerState {
+    pub counters: Counters,
+    /// Text messages are relayed to every connected client through here.
+    pub relay: broadcast::Sender<String>,
+    pub policy: TextPolicy,
+    /// How many recent text messages to replay to joining clients;
+    /// 0 disables the backlog.
+    pub history_cap: usize,
+    /// Content-address uploads: identical bytes are stored once.
+    pub dedupe: bool,
+    /// Shared secret clients must present right after the handshake;
+    /// `None` leaves the server open.
+    pub token: Option<String>,
+    /// Ring buffer of the last `history_cap` text messages.
+    history: Mutex<VecDeque<String>>,
+    /// Chunked uploads waiting for their remaining parts.
+    pending_parts: Mutex<HashMap<String, FileAssembly>>,
+    /// Pastebin texts keyed by their short random ID.
+    pastes: Mutex<HashMap<String, String>>,
+}
+
+impl ServerState {
+    pub fn new() -> Self {
+        Self::with_policy(TextPolicy::default())
+    }
+
+    pub fn with_policy(policy: TextPolicy) -> Self {
+        let (relay, _) = broadcast::channel(BROADCAST_CAPACITY);
+        ServerState {
+            counters: Counters::default(),
+            relay,
+            policy,
+            history_cap: 0,
+            dedupe: false,
+            token: None,
+            history: Mutex::new(VecDeque::new()),
+            pending_parts: Mutex::new(HashMap::new()),
+            pastes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Appends a relayed message to the backlog, dropping the oldest
+    /// entry once `history_cap` is reached.
+    fn remember(&self, text: &str) {
+        if self.history_cap == 0 {
+            return;
+        }
+        let mut history = self.history.lock().expect("history poisoned");
+        if history.len() == self.history_cap {
+            history.pop_front();
+        }
+        history.push_back(text.to_string());
+    }
+
+    /// Snapshot of the backlog, oldest first.
+    pub fn backlog(&self) -> Vec<String> {
+        self.history
+            .lock()
+            .expect("history poisoned")
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Stores `content` under a fresh short random ID and returns it.
+    pub fn store_paste(&self, content: String) -> String {
+        use rand::Rng as _;
+        let mut pastes = self.pastes.lock().expect("pastes poisoned");
+        loop {
+            let id: String = rand::thread_rng()
+                .sample_iter(rand::distributions::Alphanumeric)
+                .take(6)
+                .map(char::from)
+                .collect();
+            if !pastes.contains_key(&id) {
+                pastes.insert(id.clone(), content);
+                return id;
+            }
+        }
+    }
+
+    /// The paste stored under `id`, if any.
+    pub fn get_paste(&self, id: &str) -> Option<String> {
+        self.pastes.lock().expect("pastes poisoned").get(id).cloned()
+    }
+
+    /// Stores one part of a chunked upload. Parts may arrive in any
+    /// order; returns the reassembled bytes once all `total` parts are in.
+    pub fn add_file_part(
+        &self,
+        name: &str,
+        index: u32,
+        total: u32,
+        data: Vec<u8>,
+    ) -> Result<Option<Vec<u8>>> {
+        if total == 0 || index >= total {
+            anyhow::bail!("invalid file part {index}/{total} for {name}");
+        }
+
+        let mut pending = self.pending_parts.lock().expect("pending_parts poisoned");
+        let assembly = pending.entry(name.to_string()).or_default();
+        if assembly.parts.is_empty() {
+            assembly.total = total;
+        } else if assembly.total != total {
+            pending.remove(name);
+            anyhow::bail!("inconsistent part count for {name}: {total} vs earlier");
+        }
+        assembly.parts.insert(index, data);
+
+        if assembly.parts.len() as u32 != total {
+            return Ok(None);
+        }
+
+        let mut assembly = pending.remove(name).expect("assembly just inserted");
+        let mut bytes = Vec::new();
+        for i in 0..total {
+            bytes.extend(assembly.parts.remove(&i).expect("all parts present"));
+        }
+        Ok(Some(bytes))
+    }
+}
+
+impl Default for ServerState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Binds the address and serves on it. Tests that need an ephemeral port
+/// can bind their own listener and call [`run_server`] directly.
+pub async fn start_server(addr: &str, state: Arc<ServerState>) -> Result<()> {
+    let listener = TcpListener::bind(addr)
+        .await
+        .with_context(|| format!("failed to bind {addr}"))?;
+    info!("Listening on {addr}");
+    run_server(listener, state).await
+}
+
+/// Mirrors relayed text messages to websocket clients on a second port,
+/// for browser dashboards. Only started when `--ws-port` is given.
+pub async fn run_ws_server(port: u16, state: Arc<ServerState>) -> Result<()> {
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .await
+        .with_context(|| format!("failed to bind websocket port {port}"))?;
+    info!("Websocket server listening on port {port}");
+
+    loop {
+        let (stream, peer) = listener.accept().await.context("websocket accept failed")?;
+        let mut relay_rx = state.relay.subscribe();
+        tokio::spawn(async move {
+            let mut ws = match tokio_tungstenite::accept_async(stream).await {
+                Ok(ws) => ws,
+                Err(e) => {
+                    error!("Websocket handshake with {peer} failed: {e}");
+                    return;
+                }
+            };
+            info!("Websocket client connected: {peer}");
+            while let Ok(text) = relay_rx.recv().await {
+                if ws
+                    .send(tokio_tungstenite::tungstenite::Message::Text(text.into()))
+                    .await
+                    .is_err()
+                {
+                    break;
+                }
+            }
+            info!("Websocket client gone: {peer}");
+        });
+    }
+}
+
+/// Serves the shared counters in Prometheus text format on
+/// `/metrics`, for scraping. Only started when `--metrics-port` is
+/// given.
+pub async fn run_metrics_server(port: u16, state: Arc<ServerState>) -> Result<()> {
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .await
+        .with_context(|| format!("failed to bind metrics port {port}"))?;
+    info!("Metrics server listening on port {port}");
+
+    let app = axum::Router::new().route(
+        "/metrics",
+        axum::routing::get(move || {
+            let state = Arc::clone(&state);
+            async move { render_metrics(&state.counters) }
+        }),
+    );
+    axum::serve(listener, app).await.context("metrics server failed")
+}
+
+/// The counters as Prometheus exposition text, one gauge-free counter
+/// per line.
+pub fn render_metrics(counters: &Counters) -> String {
+    let pairs = [
+        ("chat_connections_total", &counters.connections),
+        ("chat_text_messages_total", &counters.text_messages),
+        ("chat_files_saved_total", &counters.files_saved),
+        ("chat_images_saved_total", &counters.images_saved),
+        ("chat_bytes_received_total", &counters.bytes_received),
+    ];
+    let mut out = String::new();
+    for (name, value) in pairs {
+        out.push_str(&format!(
+            "# TYPE {name} counter\n{name} {}\n",
+            value.load(Ordering::Relaxed)
+        ));
+    }
+    out
+}
+
+/// Accept loop over an already-bound listener, running until the
+/// listener errors out.
+pub async fn run_server(listener: TcpListener, state: Arc<ServerState>) -> Result<()> {
+    run_server_with_shutdown(listener, state, std::future::pending::<()>()).await
+}
+
+/// Accept loop that, once `shutdown` resolves, stops accepting new
+/// connections and drains in-flight handlers (bounded by
+/// [`SHUTDOWN_GRACE`]) instead of aborting them mid-write.
+pub async fn run_server_with_shutdown<F>(
+    listener: TcpListener,
+    state: Arc<ServerState>,
+    shutdown: F,
+) -> Result<()>
+where
+    F: Future<Output = ()>,
+{
+    let mut handlers = JoinSet::new();
+    tokio::pin!(shutdown);
+
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => {
+                let (stream, peer) = accepted.context("accept failed")?;
+                state.counters.connections.fetch_add(1, Ordering::Relaxed);
+                let state = Arc::clone(&state);
+                handlers.spawn(async move {
+                    handle_client(stream, peer, state).await;
+                });
+            }
+            _ = &mut shutdown => break,
+        }
+    }
+    drop(listener);
+
+    if !handlers.is_empty() {
+        info!("Draining {} in-flight connection(s)", handlers.len());
+        let drain = async {
+            while handlers.join_next().await.is_some() {}
+        };
+        if tokio::time::timeout(SHUTDOWN_GRACE, drain).await.is_err() {
+            warn!("Grace period expired, aborting remaining handlers");
+        }
+    }
+    Ok(())
+}
+
+/// Tails `path` like `tail -f`, relaying each newly appended complete
+/// line to connected clients as chat text. Starts at the current end of
+/// the file; when the file shrinks or is replaced (truncation or
+/// rotation), reading restarts from the beginning. A missing file is
+/// retried quietly.
+pub async fn follow_file(path: PathBuf, state: Arc<ServerState>) {
+    let mut offset = match tokio::fs::metadata(&path).await {
+        Ok(meta) => meta.len(),
+        Err(_) => 0,
+    };
+
+    loop {
+        tokio::time::sleep(FOLLOW_POLL).await;
+        let data = match tokio::fs::read(&path).await {
+            Ok(data) => data,
+            Err(_) => {
+                offset = 0;
+                continue;
+            }
+        };
+        if (data.len() as u64) < offset {
+            offset = 0;
+        }
+
+        // Only consume complete lines; a partial write stays buffered in
+        // the file until its newline arrives.
+        let fresh = &data[offset as usize..];
+        let Some(last_newline) = fresh.iter().rposition(|&b| b == b'\n') else {
+            continue;
+        };
+        for line in String::from_utf8_lossy(&fresh[..=last_newline]).lines() {
+            let _ = state.relay.send(line.to_string());
+        }
+        offset += last_newline as u64 + 1;
+    }
+}
+
+pub async fn handle_client(stream: TcpStream, peer: SocketAddr, state: Arc<ServerState>) {
+    info!("Client connected: {peer}");
+    let (mut read_half, mut write_half) = stream.into_split();
+
+    // Version handshake: the first message must be a compatible Hello.
+    match receive_message(&mut read_half).await {
+        Ok(Message::Hello { version }) if version == PROTOCOL_VERSION => {
+            if let Err(e) = send_message(
+                &mut write_half,
+                &Message::Welcome {
+                    version: PROTOCOL_VERSION,
+                },
+            )
+            .await
+            {
+                error!("Failed to welcome {peer}: {e}");
+                return;
+            }
+        }
+        Ok(Message::Hello { version }) => {
+            info!("Rejecting {peer}: protocol version {version}, expected {PROTOCOL_VERSION}");
+            let _ = send_message(
+                &mut write_half,
+                &Message::Error(format!(
+                    "incompatible protocol version {version}, server speaks {PROTOCOL_VERSION}"
+                )),
+            )
+            .await;
+            return;
+        }
+        Ok(other) => {
+            info!("Rejecting {peer}: expected Hello, got {other:?}");
+            let _ = send_message(
+                &mut write_half,
+                &Message::Error("handshake required: send Hello first".to_string()),
+            )
+            .await;
+            return;
+        }
+        Err(e) => {
+            error!("Handshake with {peer} failed: {e}");
+            return;
+        }
+    }
+
+    // With a token configured, the next message must be a matching Auth
+    // before the client gets any backlog or relay traffic.
+    if let Some(expected) = &state.token {
+        match receive_message(&mut read_half).await {
+            Ok(Message::Auth(token))
+                if constant_time_eq(expected.as_bytes(), token.as_bytes()) => {}
+            Ok(_) => {
+                info!("Rejecting {peer}: missing or wrong auth token");
+                let _ = send_message(
+                    &mut write_half,
+                    &Message::Error("authentication required".to_string()),
+                )
+                .await;
+                return;
+            }
+            Err(e) => {
+                error!("Auth with {peer} failed: {e}");
+                return;
+            }
+        }
+    }
+
+    // Replay the backlog before any live traffic. Subscribing first
+    // means a message landing in between may arrive twice, which beats
+    // silently losing it.
+    let mut relay_rx = state.relay.subscribe();
+    for text in state.backlog() {
+        if let Err(e) = send_message(&mut write_half, &Message::Text(text)).await {
+            error!("Failed to send backlog to {peer}: {e}");
+            return;
+        }
+    }
+    let (direct_tx, mut direct_rx) = tokio::sync::mpsc::channel::<Message>(8);
+    let writer = tokio::spawn(async move {
+        loop {
+            let message = tokio::select! {
+                Ok(text) = relay_rx.recv() => Message::Text(text),
+                Some(reply) = direct_rx.recv() => reply,
+                else => break,
+            };
+            if send_message(&mut write_half, &message).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    loop {
+        let payload = match receive_frame(&mut read_half).await {
+            Ok(payload) => payload,
+            Err(e) if is_disconnect(&e) => {
+                info!("Client {peer} disconnected");
+                break;
+            }
+            Err(e) => {
+                error!("Failed to receive message from {peer}: {e}");
+                break;
+            }
+        };
+        state
+            .counters
+            .bytes_received
+            .fetch_add(payload.len() as u64, Ordering::Relaxed);
+
+        let message = match decode_message(&payload) {
+            Ok(message) => message,
+            Err(e) => {
+                error!(
+                    "Failed to deserialize message from {peer}: {e}. Raw data: {}",
+                    hex_preview(&payload, RAW_DUMP_LIMIT)
+                );
+                continue;
+            }
+        };
+
+        match process_message(message, &state).await {
+            Ok(Some(reply)) => {
+                let _ = direct_tx.send(reply).await;
+            }
+            Ok(None) => {}
+            Err(e) => error!("Failed to process message from {peer}: {e:#}"),
+        }
+    }
+
+    writer.abort();
+    info!("Client handler finished: {peer}");
+}
+
+/// Hex dump of at most the first `max` bytes, so a huge undecodable
+/// frame does not flood the error log.
+fn hex_preview(data: &[u8], max: usize) -> String {
+    let shown: String = data
+        .iter()
+        .take(max)
+        .map(|b| format!("{b:02x} "))
+        .collect::<String>()
+        .trim_end()
+        .to_string();
+    if data.len() > max {
+        format!("{shown} ... (truncated, {} bytes total)", data.len())
+    } else {
+        shown
+    }
+}
+
+/// Compares two secrets in time independent of where they differ, so a
+/// client cannot narrow down the token byte by byte from the timing.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Whether a receive error is just the peer hanging up — possibly in the
+/// middle of a frame — as opposed to a real transport problem. Expected
+/// disconnects are logged at info level to keep the error log quiet.
+fn is_disconnect(e: &std::io::Error) -> bool {
+    e.kind() == std::io::ErrorKind::UnexpectedEof
+}
+
+/// Handles one decoded message; returns a reply to send back to the
+/// sender, if any.
+pub async fn process_message(message: Message, state: &ServerState) -> Result<Option<Message>> {
+    match message {
+        Message::Text(text) => {
+            if let Some(reason) = state.policy.violation(&text) {
+                info!("Rejected text message: {reason}");
+                return Ok(Some(Message::Error(format!("message rejected: {reason}"))));
+            }
+            state.counters.text_messages.fetch_add(1, Ordering::Relaxed);
+            info!("Text: {text}");
+            state.remember(&text);
+            // Nobody listening is fine; send only fails with zero receivers.
+            let _ = state.relay.send(text);
+        }
+        Message::File { name, data } => {
+            let path = save_file(&name, &data, state.dedupe).await?;
+            state.counters.files_saved.fetch_add(1, Ordering::Relaxed);
+            info!("Saved file to {}", path.display());
+        }
+        Message::FilePart {
+            name,
+            index,
+            total,
+            data,
+        } => {
+            if let Some(bytes) = state.add_file_part(&name, index, total, data)? {
+                let path = save_file(&name, &bytes, state.dedupe).await?;
+                state.counters.files_saved.fetch_add(1, Ordering::Relaxed);
+                info!("Reassembled {total} parts into {}", path.display());
+            }
+        }
+        Message::Image(data) => {
+            let path = save_image(&data).await?;
+            state.counters.images_saved.fetch_add(1, Ordering::Relaxed);
+            info!("Saved image to {}", path.display());
+        }
+        Message::Paste(content) => {
+            let id = state.store_paste(content);
+            info!("Stored paste {id}");
+            return Ok(Some(Message::PasteStored(id)));
+        }
+        Message::PasteGet(id) => {
+            return Ok(Some(match state.get_paste(&id) {
+                Some(content) => Message::Text(content),
+                None => Message::Error(format!("no paste with id {id}")),
+            }));
+        }
+        Message::Error(e) => {
+            error!("Client reported error: {e}");
+        }
+        Message::Hello { .. } | Message::Welcome { .. } | Message::Auth(_) => {
+            // Handshake and auth messages are consumed in handle_client.
+        }
+        Message::PasteStored(_) => {
+            // Server-to-client only; ignore it from a client.
+        }
+    }
+    Ok(None)
+}
+
+/// With `dedupe`, files are content-addressed: the name is prefixed with
+/// a digest of the bytes instead of a timestamp, so identical content
+/// maps to the same path and is written only once.
+pub async fn save_file(name: &str, data: &[u8], dedupe: bool) -> Result<PathBuf> {
+    tokio::fs::create_dir_all(FILE_STORE).await?;
+    let file_name = if dedupe {
+        format!("{}_{name}", content_hash(data))
+    } else {
+        timestamped(name)
+    };
+    let path = PathBuf::from(FILE_STORE).join(file_name);
+    if dedupe && tokio::fs::try_exists(&path).await? {
+        info!("Dedupe hit, {} already stored", path.display());
+        return Ok(path);
+    }
+    tokio::fs::write(&path, data)
+        .await
+        .with_context(|| format!("failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+/// First 16 hex chars of the SHA-256 of `data`; plenty to address
+/// content without producing unwieldy file names.
+fn content_hash(data: &[u8]) -> String {
+    use sha2::Digest as _;
+    let digest = sha2::Sha256::digest(data);
+    digest[..8].iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Images are re-encoded to PNG regardless of their original format.
+pub async fn save_image(data: &[u8]) -> Result<PathBuf> {
+    tokio::fs::create_dir_all(IMAGE_STORE).await?;
+    let image = image::load_from_memory(data).context("failed to decode image")?;
+    let path = PathBuf::from(IMAGE_STORE).join(timestamped("image.png"));
+    let path_clone = path.clone();
+    // image encoding is CPU-bound and blocking.
+    tokio::task::spawn_blocking(move || image.save(&path_clone))
+        .await?
+        .context("failed to save image")?;
+    Ok(path)
+}
+
+fn timestamped(name: &str) -> String {
+    format!("{}_{}", chrono::Utc::now().timestamp(), name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tokio::io::AsyncWriteExt as _;
+
+    #[test]
+    fn file_parts_reassemble_out_of_order() {
+        let state = ServerState::new();
+        let original = b"aaaabbbbcc".to_vec();
+
+        assert!(state
+            .add_file_part("big.bin", 2, 3, original[8..].to_vec())
+            .unwrap()
+            .is_none());
+        assert!(state
+            .add_file_part("big.bin", 0, 3, original[..4].to_vec())
+            .unwrap()
+            .is_none());
+        let bytes = state
+            .add_file_part("big.bin", 1, 3, original[4..8].to_vec())
+            .unwrap()
+            .expect("last part completes the file");
+
+        assert_eq!(bytes, original);
+    }
+
+    #[tokio::test]
+    async fn half_sent_message_counts_as_a_disconnect() {
+        let (mut client, mut server) = tokio::io::duplex(64);
+        // Length prefix promising 10 bytes, but only 3 arrive.
+        client.write_all(&10u32.to_be_bytes()).await.unwrap();
+        client.write_all(b"abc").await.unwrap();
+        drop(client);
+
+        let err = receive_frame(&mut server).await.unwrap_err();
+        assert!(is_disconnect(&err), "got {err:?}");
+        assert!(!is_disconnect(&std::io::Error::other("broken pipe")));
+    }
+
+    #[tokio::test]
+    async fn dedupe_stores_identical_bytes_once() {
+        let name = "hw11_dedupe_test.bin";
+        let first = save_file(name, b"same bytes", true).await.unwrap();
+        let second = save_file(name, b"same bytes", true).await.unwrap();
+        assert_eq!(first, second);
+
+        let mut copies = 0;
+        let mut dir = tokio::fs::read_dir(FILE_STORE).await.unwrap();
+        while let Some(entry) = dir.next_entry().await.unwrap() {
+            if entry.file_name().to_string_lossy().ends_with(name) {
+                copies += 1;
+            }
+        }
+        assert_eq!(copies, 1);
+
+        let other = save_file(name, b"other bytes", true).await.unwrap();
+        assert_ne!(first, other);
+
+        tokio::fs::remove_file(first).await.unwrap();
+        tokio::fs::remove_file(other).await.unwrap();
+    }
+
+    #[test]
+    fn metrics_render_every_counter_in_prometheus_format() {
+        let counters = Counters::default();
+        counters.connections.fetch_add(3, Ordering::Relaxed);
+        counters.bytes_received.fetch_add(1024, Ordering::Relaxed);
+
+        let out = render_metrics(&counters);
+        assert!(out.contains("chat_connections_total 3"), "got:\n{out}");
+        assert!(out.contains("chat_bytes_received_total 1024"));
+        for name in [
+            "chat_text_messages_total 0",
+            "chat_files_saved_total 0",
+            "chat_images_saved_total 0",
+        ] {
+            assert!(out.contains(name), "missing {name}:\n{out}");
+        }
+    }
+
+    #[test]
+    fn large_bad_buffer_is_dumped_truncated_as_hex() {
+        let data = vec![0xABu8; 1000];
+        let dump = hex_preview(&data, 4);
+        assert_eq!(dump, "ab ab ab ab ... (truncated, 1000 bytes total)");
+
+        // Short buffers are shown whole, without the suffix.
+        assert_eq!(hex_preview(&[0x01, 0xFF], 4), "01 ff");
+    }
+
+    #[test]
+    fn invalid_file_part_is_rejected() {
+        let state = ServerState::new();
+        assert!(state.add_file_part("x", 3, 3, vec![1]).is_err());
+        assert!(state.add_file_part("x", 0, 0, vec![1]).is_err());
+    }
+
+    #[tokio::test]
+    async fn paste_is_stored_and_retrieved_by_its_id() {
+        let state = ServerState::new();
+
+        let reply = process_message(Message::Paste("stashed text".to_string()), &state)
+            .await
+            .unwrap();
+        let id = match reply {
+            Some(Message::PasteStored(id)) => id,
+            other => panic!("expected PasteStored, got {other:?}"),
+        };
+        assert_eq!(id.len(), 6);
+
+        let reply = process_message(Message::PasteGet(id), &state).await.unwrap();
+        assert!(matches!(reply, Some(Message::Text(t)) if t == "stashed text"));
+
+        let reply = process_message(Message::PasteGet("nope42".to_string()), &state)
+            .await
+            .unwrap();
+        assert!(matches!(reply, Some(Message::Error(_))));
+    }
+
+    #[tokio::test]
+    async fn text_message_is_pushed_onto_the_relay_channel() {
+        let state = ServerState::new();
+        let mut relay_rx = state.relay.subscribe();
+
+        let reply = process_message(Message::Text("for the dashboard".to_string()), &state)
+            .await
+            .unwrap();
+
+        assert!(reply.is_none());
+        assert_eq!(relay_rx.recv().await.unwrap(), "for the dashboard");
+    }
+
+    #[tokio::test]
+    async fn follow_file_broadcasts_appended_lines() {
+        let path = std::env::temp_dir().join("hw11_follow_test.log");
+        tokio::fs::write(&path, "old line\n").await.unwrap();
+
+        let state = Arc::new(ServerState::new());
+        let mut relay_rx = state.relay.subscribe();
+        let follower = tokio::spawn(follow_file(path.clone(), Arc::clone(&state)));
+        // Give the follower time to record the starting offset, so the
+        // append below is seen as new data.
+        tokio::time::sleep(Duration::from_millis(150)).await;
+
+        tokio::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .await
+            .unwrap()
+            .write_all(b"fresh line\n")
+            .await
+            .unwrap();
+
+        let line = tokio::time::timeout(Duration::from_secs(5), relay_rx.recv())
+            .await
+            .expect("appended line never reached the relay")
+            .unwrap();
+        assert_eq!(line, "fresh line");
+
+        follower.abort();
+        tokio::fs::remove_file(&path).await.ok();
+    }
+
+    #[tokio::test]
+    async fn text_policy_rejects_and_passes() {
+        let state = ServerState::with_policy(TextPolicy {
+            max_text_len: Some(10),
+            blocklist: Some(regex::RegexSet::new([r"(?i)spam"]).unwrap()),
+        });
+        let mut relay_rx = state.relay.subscribe();
+
+        let reply = process_message(Message::Text("x".repeat(11)), &state)
+            .await
+            .unwrap();
+        assert!(matches!(reply, Some(Message::Error(e)) if e.contains("limit is 10")));
+
+        let reply = process_message(Message::Text("buy SPAM".to_string()), &state)
+            .await
+            .unwrap();
+        assert!(matches!(reply, Some(Message::Error(e)) if e.contains("blocklist")));
+
+        let reply = process_message(Message::Text("hello".to_string()), &state)
+            .await
+            .unwrap();
+        assert!(reply.is_none());
+        assert_eq!(state.counters.text_messages.load(Ordering::Relaxed), 1);
+        assert_eq!(relay_rx.recv().await.unwrap(), "hello");
+    }
+}
@@ -0,0 +1,108 @@
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result};
+use clap::Parser;
+use tokio::net::TcpListener;
+use tracing::info;
+
+use server::{follow_file, run_server_with_shutdown, run_ws_server, ServerState, TextPolicy};
+
+#[derive(Debug, Parser)]
+#[command(about = "Chat server: receives text, files, and images from clients")]
+struct Args {
+    #[arg(long, default_value = "127.0.0.1")]
+    host: String,
+    #[arg(long, default_value_t = 11111)]
+    port: u16,
+    /// Also mirror text messages to websocket clients on this port.
+    #[arg(long)]
+    ws_port: Option<u16>,
+    /// Reject text messages longer than this many chars.
+    #[arg(long)]
+    max_text_len: Option<usize>,
+    /// File with one blocklist regex per line; matching text is rejected.
+    #[arg(long)]
+    blocklist: Option<std::path::PathBuf>,
+    /// Tail this file and relay appended lines to connected clients.
+    #[arg(long)]
+    follow: Option<std::path::PathBuf>,
+    /// Replay the last N text messages to newly connected clients.
+    #[arg(long, default_value_t = 0)]
+    history: usize,
+    /// Content-address uploads so identical bytes are stored once.
+    #[arg(long)]
+    dedupe: bool,
+    /// Require clients to authenticate with this token after the handshake.
+    #[arg(long)]
+    token: Option<String>,
+    /// Expose Prometheus counters over HTTP on this port.
+    #[arg(long)]
+    metrics_port: Option<u16>,
+}
+
+fn load_policy(args: &Args) -> Result<TextPolicy> {
+    let blocklist = match &args.blocklist {
+        None => None,
+        Some(path) => {
+            let patterns = std::fs::read_to_string(path)
+                .with_context(|| format!("failed to read blocklist {}", path.display()))?;
+            let patterns: Vec<&str> = patterns
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .collect();
+            Some(regex::RegexSet::new(&patterns).context("invalid blocklist regex")?)
+        }
+    };
+    Ok(TextPolicy {
+        max_text_len: args.max_text_len,
+        blocklist,
+    })
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
+    let args = Args::parse();
+    let addr = format!("{}:{}", args.host, args.port);
+
+    let mut state = ServerState::with_policy(load_policy(&args)?);
+    state.history_cap = args.history;
+    state.dedupe = args.dedupe;
+    state.token = args.token;
+    let state = Arc::new(state);
+
+    if let Some(ws_port) = args.ws_port {
+        let ws_state = Arc::clone(&state);
+        tokio::spawn(async move {
+            if let Err(e) = run_ws_server(ws_port, ws_state).await {
+                tracing::error!("Websocket server failed: {e:#}");
+            }
+        });
+    }
+
+    if let Some(metrics_port) = args.metrics_port {
+        let metrics_state = Arc::clone(&state);
+        tokio::spawn(async move {
+            if let Err(e) = server::run_metrics_server(metrics_port, metrics_state).await {
+                tracing::error!("Metrics server failed: {e:#}");
+            }
+        });
+    }
+
+    if let Some(path) = args.follow {
+        tokio::spawn(follow_file(path, Arc::clone(&state)));
+    }
+
+    let listener = TcpListener::bind(&addr)
+        .await
+        .with_context(|| format!("failed to bind {addr}"))?;
+    info!("Listening on {addr}");
+
+    // Ctrl-C stops accepting; in-flight transfers get a grace period.
+    run_server_with_shutdown(listener, state, async {
+        let _ = tokio::signal::ctrl_c().await;
+        info!("Shutting down");
+    })
+    .await
+}
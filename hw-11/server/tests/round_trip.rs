@@ -0,0 +1,167 @@
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::net::{TcpListener, TcpStream};
+
+use common::{receive_message, send_message, Message, PROTOCOL_VERSION};
+use server::{run_server, ServerState};
+
+/// Binds an ephemeral port, spawns the server on it, and returns the
+/// address plus the shared state.
+async fn spawn_server() -> (std::net::SocketAddr, Arc<ServerState>) {
+    spawn_server_with(ServerState::new()).await
+}
+
+async fn spawn_server_with(state: ServerState) -> (std::net::SocketAddr, Arc<ServerState>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let state = Arc::new(state);
+    let server_state = Arc::clone(&state);
+    tokio::spawn(async move {
+        let _ = run_server(listener, server_state).await;
+    });
+    (addr, state)
+}
+
+async fn handshake(addr: std::net::SocketAddr) -> TcpStream {
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    send_message(
+        &mut stream,
+        &Message::Hello {
+            version: PROTOCOL_VERSION,
+        },
+    )
+    .await
+    .unwrap();
+    let reply = receive_message(&mut stream).await.unwrap();
+    assert!(matches!(reply, Message::Welcome { .. }), "got {reply:?}");
+    stream
+}
+
+/// Full socket round trip: real server on an ephemeral port, real client
+/// connection, one text message, observed through the shared counters.
+#[tokio::test]
+async fn text_message_round_trips_through_the_socket() {
+    let (addr, state) = spawn_server().await;
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    send_message(
+        &mut stream,
+        &Message::Hello {
+            version: PROTOCOL_VERSION,
+        },
+    )
+    .await
+    .unwrap();
+    let reply = receive_message(&mut stream).await.unwrap();
+    assert!(matches!(reply, Message::Welcome { version } if version == PROTOCOL_VERSION));
+
+    send_message(&mut stream, &Message::Text("hello server".to_string()))
+        .await
+        .unwrap();
+
+    // The handler runs on its own task; give it a moment to process.
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+    loop {
+        if state.counters.text_messages.load(Ordering::Relaxed) == 1 {
+            break;
+        }
+        assert!(
+            tokio::time::Instant::now() < deadline,
+            "server never processed the text message"
+        );
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    assert_eq!(state.counters.connections.load(Ordering::Relaxed), 1);
+}
+
+/// A client joining after the fact gets the buffered history before any
+/// live traffic.
+#[tokio::test]
+async fn late_joiner_receives_the_backlog() {
+    let mut state = ServerState::new();
+    state.history_cap = 16;
+    let (addr, state) = spawn_server_with(state).await;
+
+    let mut sender = handshake(addr).await;
+    send_message(&mut sender, &Message::Text("first".to_string()))
+        .await
+        .unwrap();
+    send_message(&mut sender, &Message::Text("second".to_string()))
+        .await
+        .unwrap();
+
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+    while state.counters.text_messages.load(Ordering::Relaxed) < 2 {
+        assert!(
+            tokio::time::Instant::now() < deadline,
+            "server never processed both messages"
+        );
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    let mut late = handshake(addr).await;
+    let first = receive_message(&mut late).await.unwrap();
+    let second = receive_message(&mut late).await.unwrap();
+    assert!(matches!(first, Message::Text(ref t) if t == "first"), "got {first:?}");
+    assert!(matches!(second, Message::Text(ref t) if t == "second"));
+}
+
+/// With `--token`, a wrong secret gets an error and a closed connection;
+/// the right one lets messages through.
+#[tokio::test]
+async fn token_auth_rejects_wrong_secret_and_accepts_the_right_one() {
+    let mut state = ServerState::new();
+    state.token = Some("hunter2".to_string());
+    let (addr, state) = spawn_server_with(state).await;
+
+    let mut stream = handshake(addr).await;
+    send_message(&mut stream, &Message::Auth("wrong".to_string()))
+        .await
+        .unwrap();
+    let reply = receive_message(&mut stream).await.unwrap();
+    assert!(matches!(reply, Message::Error(_)), "got {reply:?}");
+    assert!(receive_message(&mut stream).await.is_err());
+
+    let mut stream = handshake(addr).await;
+    send_message(&mut stream, &Message::Auth("hunter2".to_string()))
+        .await
+        .unwrap();
+    send_message(&mut stream, &Message::Text("let me in".to_string()))
+        .await
+        .unwrap();
+
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+    while state.counters.text_messages.load(Ordering::Relaxed) == 0 {
+        assert!(
+            tokio::time::Instant::now() < deadline,
+            "authenticated message was never processed"
+        );
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
+#[tokio::test]
+async fn mismatched_protocol_version_is_rejected() {
+    let (addr, state) = spawn_server().await;
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    send_message(
+        &mut stream,
+        &Message::Hello {
+            version: PROTOCOL_VERSION + 1,
+        },
+    )
+    .await
+    .unwrap();
+
+    let reply = receive_message(&mut stream).await.unwrap();
+    assert!(matches!(reply, Message::Error(_)), "got {reply:?}");
+
+    // The server closes the connection after the rejection.
+    let next = receive_message(&mut stream).await;
+    assert!(next.is_err());
+    assert_eq!(state.counters.text_messages.load(Ordering::Relaxed), 0);
+}
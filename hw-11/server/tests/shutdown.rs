@@ -0,0 +1,66 @@
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+
+use common::{receive_message, send_message, Message, PROTOCOL_VERSION};
+use server::{run_server_with_shutdown, ServerState};
+
+/// A connection that is live when shutdown fires keeps being served
+/// until it finishes; only then does the server future return.
+#[tokio::test]
+async fn in_flight_handler_completes_before_shutdown_returns() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let state = Arc::new(ServerState::new());
+    let server_state = Arc::clone(&state);
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    let server = tokio::spawn(async move {
+        run_server_with_shutdown(listener, server_state, async {
+            let _ = shutdown_rx.await;
+        })
+        .await
+    });
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    send_message(
+        &mut stream,
+        &Message::Hello {
+            version: PROTOCOL_VERSION,
+        },
+    )
+    .await
+    .unwrap();
+    let reply = receive_message(&mut stream).await.unwrap();
+    assert!(matches!(reply, Message::Welcome { .. }));
+
+    // Trigger shutdown while the connection is still open, then keep
+    // using it: the drain must let this message through.
+    shutdown_tx.send(()).unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(!server.is_finished(), "server quit with a live connection");
+
+    send_message(&mut stream, &Message::Text("during drain".to_string()))
+        .await
+        .unwrap();
+
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+    while state.counters.text_messages.load(Ordering::Relaxed) == 0 {
+        assert!(
+            tokio::time::Instant::now() < deadline,
+            "message sent during drain was never processed"
+        );
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    // Closing the connection lets the handler (and thus shutdown) finish.
+    drop(stream);
+    tokio::time::timeout(Duration::from_secs(5), server)
+        .await
+        .expect("shutdown did not return after the handler finished")
+        .unwrap()
+        .unwrap();
+}